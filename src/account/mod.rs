@@ -1,17 +1,154 @@
-//! Functionality to alter a user's public profile.
+//! Functionality to look up the authenticated user's account and alter their public profile.
 //!
 //! Specifically, this module contains functions which update the information
 //! that is publically visible on a user's timeline (e.g. name, location). This module does *not*
-//! modify a user's private account settings (e.g. email, password).
+//! modify a user's private account settings (e.g. email, password), though `settings` can load
+//! some of them, and `verify_credentials` can load the user's own profile.
+
+use serde::Deserialize;
 
 use crate::{
     auth,
-    common::{post, request_with_empty_response, request_with_json_response, ParamList},
-    error, links,
+    common::{get, post, request_with_empty_response, request_with_json_response, ParamList},
+    error, links, trend,
     user::TwitterUser,
     Response,
 };
 
+/// Loads the profile of the authenticated user, with some extra options.
+///
+/// This is the standard way to check which user a token belongs to. Call `send` to load the
+/// profile; before that, `include_email` and `skip_status` can change what's returned with it.
+/// `auth::verify_tokens` makes the same call with the default options.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// let me = egg_mode::account::verify_credentials()
+///     .include_email(true)
+///     .send(&token)
+///     .await
+///     .unwrap();
+/// println!("signed in as @{} ({:?})", me.screen_name, me.email);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VerifyCredentials {
+    include_email: bool,
+    skip_status: bool,
+}
+
+impl VerifyCredentials {
+    /// Sets whether to ask for the user's email address, which is returned in
+    /// `TwitterUser::email`.
+    ///
+    /// Twitter only returns the email address if the app has been granted permission to request
+    /// it, and the user has a confirmed email address.
+    pub fn include_email(self, include_email: bool) -> Self {
+        VerifyCredentials {
+            include_email,
+            ..self
+        }
+    }
+
+    /// Sets whether to leave the user's most recent tweet out of the returned profile.
+    pub fn skip_status(self, skip_status: bool) -> Self {
+        VerifyCredentials {
+            skip_status,
+            ..self
+        }
+    }
+
+    fn params(&self) -> ParamList {
+        let mut params = ParamList::new();
+        if self.include_email {
+            params.add_param_ref("include_email", "true");
+        }
+        if self.skip_status {
+            params.add_param_ref("skip_status", "true");
+        }
+        params
+    }
+
+    /// Loads the authenticated user's profile.
+    pub async fn send(self, token: &auth::Token) -> error::Result<Response<TwitterUser>> {
+        let params = self.params();
+        let req = get(links::auth::VERIFY_CREDENTIALS, token, Some(&params));
+        request_with_json_response(req).await
+    }
+}
+
+/// Begins loading the authenticated user's profile. See the docs for `VerifyCredentials` for
+/// details.
+pub fn verify_credentials() -> VerifyCredentials {
+    VerifyCredentials::default()
+}
+
+/// The authenticated user's account settings, as returned by `settings`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountSettings {
+    /// The user's screen name.
+    pub screen_name: String,
+    /// Whether the user's tweets are protected, so only their followers can see them.
+    pub protected: bool,
+    /// Language code for the user's interface language, as a [BCP 47][] tag.
+    ///
+    /// [BCP 47]: https://tools.ietf.org/html/bcp47
+    pub language: String,
+    /// The user's time zone, if they've set one.
+    pub time_zone: Option<TimeZone>,
+    /// The hours during which Twitter won't send the user notifications.
+    pub sleep_time: SleepTime,
+    /// The locations the user has picked to see trending topics for.
+    #[serde(default)]
+    pub trend_location: Vec<trend::TrendLocation>,
+    /// Whether the user lets their tweets be tagged with a location.
+    #[serde(default)]
+    pub geo_enabled: bool,
+    /// Whether other users can find the user by their email address.
+    #[serde(default)]
+    pub discoverable_by_email: bool,
+    /// Whether the user has chosen to see media that may be sensitive without a warning.
+    #[serde(default)]
+    pub display_sensitive_media: bool,
+    /// Who can send the user direct messages, like `"following"` or `"all"`.
+    pub allow_dms_from: Option<String>,
+}
+
+/// A time zone, as set in the user's account settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeZone {
+    /// The display name of the time zone, like `"Pacific Time (US & Canada)"`.
+    pub name: String,
+    /// The name of the time zone in the [tz database][], like `"America/Los_Angeles"`.
+    ///
+    /// [tz database]: https://www.iana.org/time-zones
+    pub tzinfo_name: String,
+    /// The time zone's offset from UTC, in seconds.
+    pub utc_offset: i32,
+}
+
+/// The hours during which Twitter won't send the user notifications.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SleepTime {
+    /// Whether the user has turned sleep time on.
+    pub enabled: bool,
+    /// The hour sleep time starts, from 0 to 23, if it's turned on.
+    pub start_time: Option<u32>,
+    /// The hour sleep time ends, from 0 to 23, if it's turned on.
+    pub end_time: Option<u32>,
+}
+
+/// Loads the authenticated user's account settings.
+pub async fn settings(token: &auth::Token) -> error::Result<Response<AccountSettings>> {
+    let req = get(links::account::SETTINGS, token, None);
+    request_with_json_response(req).await
+}
+
 /// Options for updating the profile banner
 #[derive(Debug, Default)]
 pub struct ProfileBannerOption {
@@ -29,7 +166,12 @@ pub struct ProfileBannerOption {
     pub offset_top: Option<String>,
 }
 
+/// The most characters Twitter allows in a profile description.
+pub const MAX_DESCRIPTION_CHARS: usize = 160;
+
 /// Options for updating the user profile
+///
+/// Only the fields that are set are sent to Twitter, so the rest of the profile is left as it is.
 #[derive(Debug, Default)]
 pub struct UserProfile {
     /// Full name associated with the profile.
@@ -89,20 +231,123 @@ pub async fn update_profile_banner(
     request_with_empty_response(req).await
 }
 
+impl UserProfile {
+    fn params(self) -> error::Result<ParamList> {
+        if let Some(ref description) = self.description {
+            let length = description.chars().count();
+            if length > MAX_DESCRIPTION_CHARS {
+                return Err(error::Error::DescriptionTooLong(length));
+            }
+        }
+
+        Ok(ParamList::new()
+            .add_opt_param("name", self.name)
+            .add_opt_param("url", self.url)
+            .add_opt_param("location", self.location)
+            .add_opt_param("description", self.description)
+            .add_opt_param("profile_link_color", self.profile_link_color))
+    }
+}
+
 /// Sets some values that users are able to set under the "Account" tab of their settings page.
 /// Only the parameters specified will be updated.
+///
+/// If the given description is longer than `MAX_DESCRIPTION_CHARS`, this returns a
+/// `DescriptionTooLong` error without contacting Twitter.
 pub async fn update_profile(
     user_profile: UserProfile,
     token: &auth::Token,
 ) -> error::Result<Response<TwitterUser>> {
-    let params = ParamList::new()
-        .add_opt_param("name", user_profile.name)
-        .add_opt_param("url", user_profile.url)
-        .add_opt_param("location", user_profile.location)
-        .add_opt_param("description", user_profile.description)
-        .add_opt_param("profile_link_color", user_profile.profile_link_color);
+    let params = user_profile.params()?;
 
     let req = post(links::account::UPDATE_PROFILE, token, Some(&params));
 
     request_with_json_response(req).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_credentials, AccountSettings, UserProfile, MAX_DESCRIPTION_CHARS};
+    use crate::common::tests::load_file;
+
+    #[test]
+    fn parse_settings() {
+        let sample = load_file("sample_payloads/account_settings.json");
+        let settings: AccountSettings = serde_json::from_str(&sample).unwrap();
+
+        assert_eq!(settings.screen_name, "theSeanCook");
+        assert!(!settings.protected);
+        assert_eq!(settings.language, "en");
+        let tz = settings.time_zone.unwrap();
+        assert_eq!(tz.tzinfo_name, "America/Los_Angeles");
+        assert_eq!(tz.utc_offset, -28800);
+        assert!(settings.sleep_time.enabled);
+        assert_eq!(settings.sleep_time.start_time, Some(23));
+        assert_eq!(settings.sleep_time.end_time, Some(8));
+        assert_eq!(settings.trend_location.len(), 1);
+        assert_eq!(settings.trend_location[0].woeid, 2487956);
+        assert_eq!(
+            settings.trend_location[0].country_code.as_deref(),
+            Some("US")
+        );
+        assert_eq!(settings.allow_dms_from.as_deref(), Some("following"));
+
+        let minimal = r#"{
+            "protected": true,
+            "screen_name": "someone",
+            "language": "de",
+            "time_zone": null,
+            "sleep_time": {"enabled": false, "start_time": null, "end_time": null}
+        }"#;
+        let settings: AccountSettings = serde_json::from_str(minimal).unwrap();
+        assert!(settings.protected);
+        assert!(settings.time_zone.is_none());
+        assert!(settings.trend_location.is_empty());
+    }
+
+    #[test]
+    fn partial_profile_update() {
+        let params = UserProfile {
+            location: Some("Lisboa".to_string()),
+            ..Default::default()
+        }
+        .params()
+        .unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params.get("location").map(|v| &**v), Some("Lisboa"));
+
+        let params = UserProfile {
+            name: Some("egg".to_string()),
+            description: Some("ünïcödé ".repeat(20)),
+            ..Default::default()
+        }
+        .params()
+        .unwrap();
+        let mut keys = params.keys().map(|k| &**k).collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, ["description", "name"]);
+
+        let too_long = UserProfile {
+            description: Some("a".repeat(MAX_DESCRIPTION_CHARS + 1)),
+            ..Default::default()
+        }
+        .params();
+        assert!(matches!(
+            too_long,
+            Err(crate::error::Error::DescriptionTooLong(161))
+        ));
+    }
+
+    #[test]
+    fn verify_credentials_params() {
+        let params = verify_credentials().params();
+        assert!(params.is_empty());
+
+        let params = verify_credentials()
+            .include_email(true)
+            .skip_status(true)
+            .params();
+        assert_eq!(params.get("include_email").map(|v| &**v), Some("true"));
+        assert_eq!(params.get("skip_status").map(|v| &**v), Some("true"));
+    }
+}
@@ -55,6 +55,12 @@
 //!
 //! [invalidate]: fn.invalidate_bearer.html
 //!
+//! If your Bearer token is invalidated while your program is running, calls made with it will start
+//! failing. To recover from this automatically, wrap those calls in [`with_auto_reauth`], which
+//! asks for a new token and tries the call again.
+//!
+//! [`with_auto_reauth`]: fn.with_auto_reauth.html
+//!
 //! ### Example (Bearer Token)
 //!
 //! ```rust,no_run
@@ -273,6 +279,36 @@ impl KeyPair {
 ///
 /// [apps]: https://developer.twitter.com/en/apps
 /// [invalidate]: fn.invalidate_bearer.html
+///
+/// ## Saving a Token
+///
+/// `Token` implements serde's `Serialize` and `Deserialize`, so it can be saved with any serde
+/// format and loaded again the next time your application starts, without having to go through
+/// the sign-in process again. An Access token saves both of its `KeyPair`s, and a Bearer token
+/// saves its bearer string.
+///
+/// ```rust
+/// use egg_mode::{KeyPair, Token};
+///
+/// let token = Token::Access {
+///     consumer: KeyPair::new("consumer key", "consumer secret"),
+///     access: KeyPair::new("access key", "access secret"),
+/// };
+///
+/// // write this string to your config file...
+/// let saved = serde_json::to_string(&token).unwrap();
+///
+/// // ...and read it back next time
+/// let loaded: Token = serde_json::from_str(&saved).unwrap();
+///
+/// if let Token::Access { access, .. } = loaded {
+///     assert_eq!(access.key, "access key");
+/// }
+///
+/// let bearer = Token::Bearer("bearer string".to_string());
+/// let saved = serde_json::to_string(&bearer).unwrap();
+/// assert_eq!(saved, r#"{"Bearer":"bearer string"}"#);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Token {
     /// An OAuth Access token indicating the request is coming from a specific user.
@@ -633,6 +669,55 @@ pub async fn invalidate_bearer(con_token: &KeyPair, token: &Token) -> Result<Tok
     Ok(Token::Bearer(result.to_owned()))
 }
 
+/// The Twitter error code for "Invalid or expired token".
+const EXPIRED_TOKEN_CODE: i32 = 89;
+
+/// Run the given call with a Bearer token, requesting a new Bearer token and trying again once if
+/// Twitter says the token has expired.
+///
+/// Bearer tokens can be invalidated on Twitter's side, after which every call made with them fails
+/// with an `Unauthorized` error. For a long-running service using app-only auth, this function
+/// keeps going across an invalidation: `make_call` is called with a copy of `token`, and if the
+/// call fails with error code 89 ("Invalid or expired token"), a fresh token is loaded with
+/// `bearer_token`, stored in `token`, and `make_call` is called once more with it.
+///
+/// The call is only retried once, and only for that error code. Other authentication errors, like
+/// calling an endpoint that needs a user context, are returned as-is, since a new Bearer token
+/// wouldn't fix them. If `token` isn't a Bearer token, the call is never retried.
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let con_token = egg_mode::KeyPair::new("", "");
+/// let mut token = egg_mode::auth::bearer_token(&con_token).await.unwrap();
+///
+/// let user = egg_mode::auth::with_auto_reauth(&con_token, &mut token, |token| async move {
+///     egg_mode::user::show("rustlang", &token).await
+/// })
+/// .await
+/// .unwrap();
+/// # }
+/// ```
+pub async fn with_auto_reauth<F, Fut, T>(
+    con_token: &KeyPair,
+    token: &mut Token,
+    mut make_call: F,
+) -> Result<T>
+where
+    F: FnMut(Token) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    match make_call(token.clone()).await {
+        Err(error::Error::Unauthorized { code, .. })
+            if code == EXPIRED_TOKEN_CODE && matches!(*token, Token::Bearer(_)) =>
+        {
+            *token = bearer_token(con_token).await?;
+            make_call(token.clone()).await
+        }
+        res => res,
+    }
+}
+
 /// If the given tokens are valid, return the user information for the authenticated user.
 ///
 /// If you have cached access tokens, using this method is a convenient way to make sure they're
@@ -32,6 +32,7 @@ pub struct RequestBuilder<'a> {
     query: Option<String>,
     body: Option<(Body, &'static str)>,
     addon: OAuthAddOn,
+    parse_twitter_errors: bool,
 }
 
 impl<'a> RequestBuilder<'a> {
@@ -44,6 +45,7 @@ impl<'a> RequestBuilder<'a> {
             query: None,
             body: None,
             addon: OAuthAddOn::None,
+            parse_twitter_errors: true,
         }
     }
 
@@ -146,6 +148,21 @@ impl<'a> RequestBuilder<'a> {
         }
     }
 
+    /// Sets whether the response to this request should be checked for a Twitter error object.
+    /// Defaults to `true`.
+    ///
+    /// Normally, the `response_*` functions in the `raw` module try to parse every response body
+    /// as a list of Twitter errors, even when the request succeeded, and return `TwitterError` if
+    /// it matches. If the endpoint you're calling returns something that isn't JSON, or you want
+    /// to handle the body yourself, setting this to `false` skips that check, so that only the
+    /// HTTP status of the response is used to detect errors.
+    pub fn parse_twitter_errors(self, parse: bool) -> Self {
+        RequestBuilder {
+            parse_twitter_errors: parse,
+            ..self
+        }
+    }
+
     /// Formats this `RequestBuilder` into a complete `Request`, signing it with the given keys.
     ///
     /// While the `token` parameter is an Option here, it should only be `None` when generating a
@@ -155,7 +172,13 @@ impl<'a> RequestBuilder<'a> {
         let oauth = OAuthParams::from_keys(consumer_key.clone(), token.cloned())
             .with_addon(self.addon.clone())
             .sign_request(self.method.clone(), self.base_uri, self.params.as_ref());
-        self.request_authorization(oauth.to_string())
+        let mut request = self.request_authorization(oauth.to_string());
+        if cfg!(feature = "debug_signatures") {
+            request
+                .extensions_mut()
+                .insert(SignatureBaseString(oauth.base_str));
+        }
+        request
     }
 
     /// Formats this `RequestBuilder` into a complete `Request`, signing it with the given token.
@@ -200,6 +223,11 @@ impl<'a> RequestBuilder<'a> {
             .method(self.method)
             .uri(full_url)
             .header(AUTHORIZATION, authorization);
+        let request = if self.parse_twitter_errors {
+            request
+        } else {
+            request.extension(SkipTwitterErrors)
+        };
 
         if let Some((body, content)) = self.body {
             request.header(CONTENT_TYPE, content).body(body).unwrap()
@@ -273,40 +301,7 @@ impl OAuthParams {
     /// Uses the parameters in this `OAuthParams` instance to generate a signature for the given
     /// request, returning it as a `SignedHeader`.
     fn sign_request(self, method: Method, uri: &str, params: Option<&ParamList>) -> SignedHeader {
-        let query_string = {
-            let sig_params = params
-                .cloned()
-                .unwrap_or_default()
-                .add_param("oauth_consumer_key", self.consumer_key.key.clone())
-                .add_param("oauth_nonce", self.nonce.clone())
-                .add_param("oauth_signature_method", "HMAC-SHA1")
-                .add_param("oauth_timestamp", format!("{}", self.timestamp.clone()))
-                .add_param("oauth_version", "1.0")
-                .add_opt_param("oauth_token", self.token.clone().map(|k| k.key))
-                .add_opt_param(
-                    "oauth_callback",
-                    self.addon.as_callback().map(|s| s.to_string()),
-                )
-                .add_opt_param(
-                    "oauth_verifier",
-                    self.addon.as_verifier().map(|s| s.to_string()),
-                );
-
-            let mut query = sig_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
-                .collect::<Vec<_>>();
-            query.sort();
-
-            query.join("&")
-        };
-
-        let base_str = format!(
-            "{}&{}&{}",
-            percent_encode(method.as_str()),
-            percent_encode(uri),
-            percent_encode(&query_string)
-        );
+        let base_str = self.signature_base_string(method, uri, params);
         let key = format!(
             "{}&{}",
             percent_encode(&self.consumer_key.secret),
@@ -344,7 +339,52 @@ impl OAuthParams {
             base64::encode(&digest.finalize().into_bytes()).into(),
         );
 
-        SignedHeader { params }
+        SignedHeader { params, base_str }
+    }
+
+    /// Assembles the "signature base string" for the given request: the HTTP method, URI, and
+    /// sorted parameters (including the OAuth parameters), percent-encoded as Twitter expects.
+    /// This is the text that gets signed with the consumer and token secrets.
+    fn signature_base_string(
+        &self,
+        method: Method,
+        uri: &str,
+        params: Option<&ParamList>,
+    ) -> String {
+        let query_string = {
+            let sig_params = params
+                .cloned()
+                .unwrap_or_default()
+                .add_param("oauth_consumer_key", self.consumer_key.key.clone())
+                .add_param("oauth_nonce", self.nonce.clone())
+                .add_param("oauth_signature_method", "HMAC-SHA1")
+                .add_param("oauth_timestamp", format!("{}", self.timestamp.clone()))
+                .add_param("oauth_version", "1.0")
+                .add_opt_param("oauth_token", self.token.clone().map(|k| k.key))
+                .add_opt_param(
+                    "oauth_callback",
+                    self.addon.as_callback().map(|s| s.to_string()),
+                )
+                .add_opt_param(
+                    "oauth_verifier",
+                    self.addon.as_verifier().map(|s| s.to_string()),
+                );
+
+            let mut query = sig_params
+                .iter()
+                .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+                .collect::<Vec<_>>();
+            query.sort();
+
+            query.join("&")
+        };
+
+        format!(
+            "{}&{}&{}",
+            percent_encode(method.as_str()),
+            percent_encode(uri),
+            percent_encode(&query_string)
+        )
     }
 }
 
@@ -383,6 +423,8 @@ impl OAuthAddOn {
 struct SignedHeader {
     /// The OAuth parameters used to create the signature.
     params: BTreeMap<&'static str, Cow<'static, str>>,
+    /// The signature base string that was signed to create the signature.
+    base_str: String,
 }
 
 /// The `Display` impl for `SignedHeader` formats it as an `Authorization` header for an HTTP
@@ -476,7 +518,11 @@ pub fn post_json<B: serde::Serialize>(uri: &str, token: &Token, body: B) -> Requ
 
 #[cfg(test)]
 mod tests {
-    use super::bearer_request;
+    use super::{bearer_request, get, OAuthParams};
+    use crate::auth::Token;
+    use crate::common::{percent_encode, ParamList};
+    use hyper::header::AUTHORIZATION;
+    use hyper::Method;
 
     #[test]
     fn bearer_header() {
@@ -488,4 +534,76 @@ mod tests {
 
         assert_eq!(output, "Basic eHZ6MWV2RlM0d0VFUFRHRUZQSEJvZzpMOHFxOVBaeVJnNmllS0dFS2hab2xHQzB2SldMdzhpRUo4OERSZHlPZw==");
     }
+
+    #[test]
+    fn signature() {
+        // example taken from https://developer.twitter.com/en/docs/authentication/oauth-1-0a/creating-a-signature
+        let consumer = super::KeyPair::new(
+            "xvz1evFS4wEEPTGEFPHBog",
+            "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+        );
+        let token = super::KeyPair::new(
+            "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb",
+            "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE",
+        );
+        let oauth = OAuthParams {
+            nonce: "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg".to_string(),
+            timestamp: 1318622958,
+            ..OAuthParams::from_keys(consumer, Some(token))
+        };
+        let params = ParamList::new()
+            .add_param("include_entities", "true")
+            .add_param(
+                "status",
+                "Hello Ladies + Gentlemen, a signed OAuth request!",
+            );
+
+        let header = oauth.sign_request(
+            Method::POST,
+            "https://api.twitter.com/1.1/statuses/update.json",
+            Some(&params),
+        );
+
+        assert_eq!(header.base_str, "POST&https%3A%2F%2Fapi.twitter.com%2F1.1%2Fstatuses%2Fupdate.json&include_entities%3Dtrue%26oauth_consumer_key%3Dxvz1evFS4wEEPTGEFPHBog%26oauth_nonce%3DkYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D1318622958%26oauth_token%3D370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb%26oauth_version%3D1.0%26status%3DHello%2520Ladies%2520%252B%2520Gentlemen%252C%2520a%2520signed%2520OAuth%2520request%2521");
+        assert_eq!(
+            header.params["oauth_signature"],
+            "hCtSmYh+iHYCEqBWrE7C7hYmtUk="
+        );
+    }
+
+    #[test]
+    fn percent_encoding() {
+        assert_eq!(
+            percent_encode("a b+c!d~e-f.g_h*").to_string(),
+            "a%20b%2Bc%21d~e-f.g_h%2A"
+        );
+        assert_eq!(percent_encode("🦀").to_string(), "%F0%9F%A6%80");
+        assert_eq!(
+            ParamList::new()
+                .add_param("q", "rust lang!")
+                .to_urlencoded(),
+            "q=rust%20lang%21"
+        );
+    }
+
+    #[test]
+    fn token_authorization() {
+        let url = "https://api.twitter.com/1.1/users/show.json";
+        let params = ParamList::new().add_param("screen_name", "rustlang");
+
+        let bearer = Token::Bearer("abc".to_string());
+        let req = get(url, &bearer, Some(&params));
+        assert_eq!(req.headers()[AUTHORIZATION], "Bearer abc");
+
+        let access = Token::Access {
+            consumer: super::KeyPair::new("consumer_key", "consumer_secret"),
+            access: super::KeyPair::new("access_key", "access_secret"),
+        };
+        let req = get(url, &access, Some(&params));
+        let header = req.headers()[AUTHORIZATION].to_str().unwrap();
+        assert!(header.starts_with("OAuth "));
+        assert!(header.contains("oauth_consumer_key=\"consumer_key\""));
+        assert!(header.contains("oauth_token=\"access_key\""));
+        assert!(header.contains("oauth_signature="));
+    }
 }
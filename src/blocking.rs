@@ -0,0 +1,157 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Blocking versions of egg-mode's functions, for programs that don't use async.
+//!
+//! This module is only available with the `blocking` feature turned on.
+//!
+//! Everything else in egg-mode is an `async fn`, which needs an async runtime like tokio to run
+//! it. For a small script or command-line tool, setting one up can be more trouble than it's worth,
+//! so this module runs egg-mode's calls on a runtime of its own and waits for them to finish. The
+//! most common calls have blocking versions in the submodules here, with the same arguments as the
+//! originals. Anything else can be run with `block_on`:
+//!
+//! ```rust,no_run
+//! # use egg_mode::Token;
+//! # let token: Token = unimplemented!();
+//! use egg_mode::blocking;
+//!
+//! let user = blocking::user::show("rustlang", &token).unwrap();
+//! println!("{} has {} followers", user.screen_name, user.followers_count);
+//!
+//! let (_timeline, feed) =
+//!     blocking::block_on(egg_mode::tweet::home_timeline(&token).start()).unwrap();
+//! for tweet in feed.iter() {
+//!     println!("{}", tweet.text);
+//! }
+//! ```
+//!
+//! The runtime is started the first time it's needed, and lives for the rest of the program. Its
+//! worker threads keep open connections to Twitter alive between calls, so a blocking call can be
+//! made from any thread, and the data it returns doesn't borrow from anything.
+//!
+//! The functions in this module can't be called from inside an async context, such as inside a
+//! function running on a tokio runtime. Doing so will panic. Use the async functions there instead.
+
+use std::future::Future;
+
+use lazy_static::lazy_static;
+use tokio::runtime::{Builder, Runtime};
+
+lazy_static! {
+    static ref RUNTIME: Runtime = Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("egg-mode-blocking")
+        .enable_all()
+        .build()
+        .expect("could not start the runtime for egg-mode's blocking calls");
+}
+
+/// Runs the given future to completion on egg-mode's blocking runtime, and returns its output.
+///
+/// This can run any of egg-mode's async functions, for calls that don't have a blocking version
+/// in this module.
+///
+/// # Panics
+///
+/// This panics if it's called from inside an async context, such as from a task running on a
+/// tokio runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+/// Blocking versions of functions in the `user` module.
+pub mod user {
+    use super::block_on;
+    use crate::error::Result;
+    use crate::user::{self, TwitterUser, UserID};
+    use crate::{auth, Response};
+
+    /// Blocking version of `user::show`.
+    pub fn show<T: Into<UserID>>(acct: T, token: &auth::Token) -> Result<Response<TwitterUser>> {
+        block_on(user::show(acct, token))
+    }
+
+    /// Blocking version of `user::lookup`.
+    pub fn lookup<T, I>(accts: I, token: &auth::Token) -> Result<Response<Vec<TwitterUser>>>
+    where
+        T: Into<UserID>,
+        I: IntoIterator<Item = T>,
+    {
+        block_on(user::lookup(accts, token))
+    }
+}
+
+/// Blocking versions of functions in the `tweet` module.
+pub mod tweet {
+    use super::block_on;
+    use crate::error::Result;
+    use crate::tweet::{self, DraftTweet, Tweet};
+    use crate::{auth, Response};
+
+    /// Blocking version of `tweet::show`.
+    pub fn show(id: u64, token: &auth::Token) -> Result<Response<Tweet>> {
+        block_on(tweet::show(id, token))
+    }
+
+    /// Blocking version of `tweet::lookup`.
+    pub fn lookup<I: IntoIterator<Item = u64>>(
+        ids: I,
+        token: &auth::Token,
+    ) -> Result<Response<Vec<Tweet>>> {
+        block_on(tweet::lookup(ids, token))
+    }
+
+    /// Blocking version of `DraftTweet::send`.
+    pub fn send(draft: &DraftTweet, token: &auth::Token) -> Result<Response<Tweet>> {
+        block_on(draft.send(token))
+    }
+}
+
+/// Blocking versions of functions in the `auth` module.
+pub mod auth {
+    use super::block_on;
+    use crate::auth::{self, Token};
+    use crate::error::Result;
+    use crate::user::TwitterUser;
+    use crate::Response;
+
+    /// Blocking version of `auth::verify_tokens`.
+    pub fn verify_tokens(token: &Token) -> Result<Response<TwitterUser>> {
+        block_on(auth::verify_tokens(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_on;
+    use crate::common::tests::serve;
+    use crate::common::{request_with_json_response, Response};
+
+    #[test]
+    fn blocking_request() {
+        let body = r#"{"answer":42}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nx-rate-limit-limit: 15\r\nx-rate-limit-remaining: 14\r\nx-rate-limit-reset: 1600000000\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (addr, _) = serve(response.into_bytes());
+
+        let load = move || {
+            let request = hyper::Request::get(format!("http://{}/", addr))
+                .body(hyper::Body::empty())
+                .unwrap();
+            block_on(request_with_json_response::<serde_json::Value>(request)).unwrap()
+        };
+
+        let resp: Response<serde_json::Value> = load();
+        assert_eq!(resp["answer"], 42);
+        assert_eq!(resp.rate_limit_status.remaining, 14);
+
+        // the returned data doesn't borrow from the runtime, and other threads can use it too
+        let from_thread = std::thread::spawn(load).join().unwrap();
+        assert_eq!(from_thread.response, resp.response);
+    }
+}
@@ -0,0 +1,157 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A configurable delay policy shared by everything in egg-mode that needs to wait and retry.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+// n.b. this type is re-exported at the crate root - these docs are public!
+/// A policy describing how long to wait between successive attempts at something.
+///
+/// Anything in egg-mode that retries a request, reconnects a stream, or paces a batch of calls
+/// takes one of these, so that delays can be tuned the same way across the crate. A `Backoff`
+/// starts out at its first delay, and each call to `next_delay` advances it to the next step. Once
+/// the operation succeeds, call `reset` to start over from the beginning.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use egg_mode::Backoff;
+///
+/// let mut backoff = Backoff::exponential(Duration::from_secs(5), 2, Duration::from_secs(320));
+///
+/// assert_eq!(backoff.next_delay(), Duration::from_secs(5));
+/// assert_eq!(backoff.next_delay(), Duration::from_secs(10));
+/// assert_eq!(backoff.next_delay(), Duration::from_secs(20));
+///
+/// backoff.reset();
+/// assert_eq!(backoff.next_delay(), Duration::from_secs(5));
+/// ```
+///
+/// Twitter's [streaming guide][connecting] recommends a linear backoff for network errors and an
+/// exponential backoff for HTTP errors, which can be expressed with the `linear` and
+/// `exponential` constructors respectively. Either can be combined with `jitter` to spread out
+/// the delays of several clients that fail at the same time.
+///
+/// [connecting]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/connecting
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    kind: BackoffKind,
+    cap: Duration,
+    jitter: u32,
+    attempt: u32,
+}
+
+#[derive(Debug, Clone)]
+enum BackoffKind {
+    Linear { step: Duration },
+    Exponential { base: Duration, factor: u32 },
+}
+
+impl Backoff {
+    /// Creates a `Backoff` that waits `step` longer after each attempt, up to a maximum of `cap`.
+    ///
+    /// The first delay returned by `next_delay` is `step` itself.
+    pub fn linear(step: Duration, cap: Duration) -> Backoff {
+        Backoff {
+            kind: BackoffKind::Linear { step },
+            cap,
+            jitter: 0,
+            attempt: 0,
+        }
+    }
+
+    /// Creates a `Backoff` that starts by waiting `base`, and multiplies the delay by `factor`
+    /// after each attempt, up to a maximum of `cap`.
+    pub fn exponential(base: Duration, factor: u32, cap: Duration) -> Backoff {
+        Backoff {
+            kind: BackoffKind::Exponential { base, factor },
+            cap,
+            jitter: 0,
+            attempt: 0,
+        }
+    }
+
+    /// Randomizes each delay by up to `pct` percent in either direction.
+    ///
+    /// The jitter is applied after the delay has been capped, so a delay at the cap may come out
+    /// slightly longer than the cap itself. Values above 100 are treated as 100.
+    pub fn jitter(self, pct: u32) -> Backoff {
+        Backoff {
+            jitter: pct.min(100),
+            ..self
+        }
+    }
+
+    /// Returns how long to wait before the next attempt, and advances to the following step.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = match self.kind {
+            BackoffKind::Linear { step } => step
+                .checked_mul(self.attempt.saturating_add(1))
+                .unwrap_or(self.cap),
+            BackoffKind::Exponential { base, factor } => factor
+                .checked_pow(self.attempt)
+                .and_then(|mult| base.checked_mul(mult))
+                .unwrap_or(self.cap),
+        };
+        let delay = delay.min(self.cap);
+
+        self.attempt = self.attempt.saturating_add(1);
+
+        if self.jitter == 0 {
+            delay
+        } else {
+            let spread = self.jitter as f64 / 100.0;
+            let scale = rand::thread_rng().gen_range(1.0 - spread..=1.0 + spread);
+            delay.mul_f64(scale)
+        }
+    }
+
+    /// Returns this `Backoff` to its first step, for example after a successful attempt.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_steps_and_caps() {
+        let mut backoff = Backoff::linear(Duration::from_millis(250), Duration::from_secs(1));
+
+        assert_eq!(backoff.next_delay(), Duration::from_millis(250));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(500));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(750));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn exponential_does_not_overflow() {
+        let mut backoff = Backoff::exponential(Duration::from_secs(5), 2, Duration::from_secs(320));
+
+        for _ in 0..100 {
+            assert!(backoff.next_delay() <= Duration::from_secs(320));
+        }
+        assert_eq!(backoff.next_delay(), Duration::from_secs(320));
+    }
+
+    #[test]
+    fn jitter_stays_in_range() {
+        let mut backoff =
+            Backoff::linear(Duration::from_secs(10), Duration::from_secs(10)).jitter(20);
+
+        for _ in 0..100 {
+            let delay = backoff.next_delay();
+            assert!(delay >= Duration::from_secs(8));
+            assert!(delay <= Duration::from_secs(12));
+        }
+    }
+}
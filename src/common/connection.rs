@@ -0,0 +1,523 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Infrastructure for configuring the HTTP client egg-mode uses to connect to Twitter.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use hyper::client::connect::Connect;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderValue, USER_AGENT};
+use hyper::{Body, Method, Request, StatusCode, Uri};
+
+use super::Headers;
+
+#[cfg(not(any(feature = "native_tls", feature = "rustls", feature = "rustls_webpki")))]
+compile_error!(
+    "Crate `egg_mode` must be compiled with exactly one of the three \
+feature flags `native_tls`, `rustls` or `rustls_webpki` enabled, you attempted to \
+compile `egg_mode` with none of them enabled"
+);
+
+#[cfg(any(
+    all(
+        feature = "native_tls",
+        any(feature = "rustls", feature = "rustls_webpki")
+    ),
+    all(
+        feature = "rustls",
+        any(feature = "native_tls", feature = "rustls_webpki")
+    ),
+    all(
+        feature = "rustls_webpki",
+        any(feature = "native_tls", feature = "rustls")
+    ),
+))]
+compile_error!(
+    "features `egg_mode/native_tls`, `egg_mode/rustls` and \
+`egg_mode/rustls_webpki` are mutually exclusive, you attempted to compile `egg_mode` \
+with more than one of these feature flags enabled at the same time"
+);
+
+// n.b. this type alias is re-exported in the `raw` module - these docs are public!
+/// The HTTPS connector egg-mode uses to connect to Twitter.
+///
+/// This depends on which TLS feature egg-mode was compiled with: with `native_tls` it's the
+/// `HttpsConnector` from `hyper-tls`, and with `rustls` or `rustls_webpki` it's the
+/// `HttpsConnector` from `hyper-rustls`. Either way, it wraps hyper's own `HttpConnector`.
+#[cfg(feature = "native_tls")]
+pub type HttpsConnector = hyper_tls::HttpsConnector<HttpConnector>;
+
+// n.b. this type alias is re-exported in the `raw` module - these docs are public!
+/// The HTTPS connector egg-mode uses to connect to Twitter.
+///
+/// This depends on which TLS feature egg-mode was compiled with: with `native_tls` it's the
+/// `HttpsConnector` from `hyper-tls`, and with `rustls` or `rustls_webpki` it's the
+/// `HttpsConnector` from `hyper-rustls`. Either way, it wraps hyper's own `HttpConnector`.
+#[cfg(any(feature = "rustls", feature = "rustls_webpki"))]
+pub type HttpsConnector = hyper_rustls::HttpsConnector<HttpConnector>;
+
+// n.b. this type alias is re-exported in the `raw` module - these docs are public!
+/// The `hyper::Client` type egg-mode uses to send requests to Twitter.
+pub type HttpsClient = hyper::Client<HttpsConnector>;
+
+#[cfg(feature = "native_tls")]
+fn new_https_connector() -> HttpsConnector {
+    hyper_tls::HttpsConnector::new()
+}
+
+#[cfg(feature = "rustls")]
+fn new_https_connector() -> HttpsConnector {
+    hyper_rustls::HttpsConnector::with_native_roots()
+}
+
+#[cfg(feature = "rustls_webpki")]
+fn new_https_connector() -> HttpsConnector {
+    hyper_rustls::HttpsConnector::with_webpki_roots()
+}
+
+// n.b. this type alias is re-exported in the `raw` module - these docs are public!
+/// The future returned by an [`HttpTransport`], which resolves to the response to a request.
+///
+/// [`HttpTransport`]: trait.HttpTransport.html
+pub type TransportFuture =
+    Pin<Box<dyn Future<Output = hyper::Result<hyper::Response<Body>>> + Send>>;
+
+// n.b. this trait is re-exported in the `raw` module - these docs are public!
+/// Something that can send egg-mode's requests and hand back the responses.
+///
+/// By default, egg-mode sends its requests with a `hyper::Client`, which implements this trait.
+/// Setting a different transport with [`ConnectionConfig::transport`] or [`with_transport`] lets
+/// you route requests somewhere else; in particular, [`MockTransport`] answers them with canned
+/// responses, to test code that uses egg-mode without connecting to Twitter.
+///
+/// The transport receives requests after egg-mode has signed them and added any headers from the
+/// `ConnectionConfig`, and egg-mode checks the responses for errors and rate-limit information the
+/// same way no matter which transport sent them.
+///
+/// [`ConnectionConfig::transport`]: struct.ConnectionConfig.html#method.transport
+/// [`with_transport`]: fn.with_transport.html
+/// [`MockTransport`]: struct.MockTransport.html
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    /// Sends the given request, returning a future that resolves to its response.
+    fn send(&self, request: Request<Body>) -> TransportFuture;
+}
+
+impl<C> HttpTransport for hyper::Client<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    fn send(&self, request: Request<Body>) -> TransportFuture {
+        Box::pin(self.request(request))
+    }
+}
+
+// n.b. this type is re-exported in the `raw` module - these docs are public!
+/// An [`HttpTransport`] that answers every request with a canned response, without connecting to
+/// anything.
+///
+/// Give the responses to send with `respond`, in the order the requests will be made. Each request
+/// takes the next response in line, and once only one is left, it's sent for every request after
+/// that. The requests themselves are recorded, so a test can check what was sent with `requests`.
+/// Clones of a `MockTransport` share the same responses and recorded requests, so a test can keep
+/// one clone and hand another to egg-mode.
+///
+/// ```rust
+/// use egg_mode::raw::{self, MockTransport};
+/// use hyper::{HeaderMap, StatusCode};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let token = egg_mode::Token::Bearer("abc".to_string());
+/// let mock = MockTransport::new().respond(
+///     StatusCode::OK,
+///     HeaderMap::new(),
+///     r#"{"id":165262228,"screen_name":"rustlang"}"#,
+/// );
+///
+/// let resp = raw::with_transport(mock.clone(), async {
+///     let req = raw::request_get("https://api.twitter.com/1.1/users/show.json", &token, None);
+///     raw::response_json::<serde_json::Value>(req).await
+/// })
+/// .await
+/// .unwrap();
+///
+/// assert_eq!(resp["screen_name"], "rustlang");
+/// assert_eq!(mock.requests()[0].uri.path(), "/1.1/users/show.json");
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Sending a request through a `MockTransport` that hasn't been given any responses panics.
+///
+/// [`HttpTransport`]: trait.HttpTransport.html
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: VecDeque<(StatusCode, Headers, String)>,
+    requests: Vec<MockRequest>,
+}
+
+// n.b. this type is re-exported in the `raw` module - these docs are public!
+/// A request that was sent through a [`MockTransport`].
+///
+/// [`MockTransport`]: struct.MockTransport.html
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// The HTTP method of the request.
+    pub method: Method,
+    /// The URL the request was sent to, including its query string.
+    pub uri: Uri,
+    /// The headers sent with the request, including its `Authorization` header.
+    pub headers: Headers,
+    /// The body of the request.
+    pub body: Vec<u8>,
+}
+
+impl MockTransport {
+    /// Creates a new `MockTransport` with no responses.
+    pub fn new() -> MockTransport {
+        MockTransport::default()
+    }
+
+    /// Adds a response to send, after any that were already added.
+    pub fn respond(self, status: StatusCode, headers: Headers, body: impl Into<String>) -> Self {
+        self.lock()
+            .responses
+            .push_back((status, headers, body.into()));
+        self
+    }
+
+    /// Returns the requests that have been sent through this transport so far, oldest first.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn send(&self, request: Request<Body>) -> TransportFuture {
+        let mock = self.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            let mut state = mock.lock();
+            state.requests.push(MockRequest {
+                method: parts.method,
+                uri: parts.uri,
+                headers: parts.headers,
+                body: body.to_vec(),
+            });
+            let (status, headers, body) = if state.responses.len() > 1 {
+                state.responses.pop_front().unwrap()
+            } else {
+                state
+                    .responses
+                    .front()
+                    .cloned()
+                    .expect("MockTransport was sent a request without any responses to give")
+            };
+
+            let mut response = hyper::Response::new(Body::from(body));
+            *response.status_mut() = status;
+            *response.headers_mut() = headers;
+            Ok(response)
+        })
+    }
+}
+
+thread_local! {
+    // the transport set by `with_transport` for the future currently being polled on this thread
+    static SCOPED_TRANSPORT: RefCell<Option<Arc<dyn HttpTransport>>> = RefCell::new(None);
+}
+
+// n.b. this function is re-exported in the `raw` module - these docs are public!
+/// Runs the given future, sending any requests it makes through the given transport.
+///
+/// Unlike [`ConnectionConfig::transport`], this only affects requests made while polling
+/// `future`, not the rest of the process, so tests that run in parallel can each use their own
+/// [`MockTransport`]. It takes priority over the transport set in the `ConnectionConfig`.
+///
+/// Requests started by other tasks, such as ones spawned from inside `future`, are not affected.
+///
+/// [`ConnectionConfig::transport`]: struct.ConnectionConfig.html#method.transport
+/// [`MockTransport`]: struct.MockTransport.html
+pub fn with_transport<T, F>(transport: T, future: F) -> impl Future<Output = F::Output>
+where
+    T: HttpTransport + 'static,
+    F: Future,
+{
+    WithTransport {
+        transport: Arc::new(transport),
+        future: Box::pin(future),
+    }
+}
+
+struct WithTransport<F> {
+    transport: Arc<dyn HttpTransport>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for WithTransport<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // puts back whatever transport was set before, even if the inner future panics
+        struct Restore(Option<Arc<dyn HttpTransport>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                SCOPED_TRANSPORT.with(|t| *t.borrow_mut() = previous);
+            }
+        }
+
+        let this = &mut *self;
+        let previous = SCOPED_TRANSPORT.with(|t| t.replace(Some(this.transport.clone())));
+        let _restore = Restore(previous);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref CONNECTION_CONFIG: RwLock<ConnectionConfig> = RwLock::new(ConnectionConfig::new());
+}
+
+thread_local! {
+    // the client for requests sent from this thread, along with a marker that goes away when the
+    // runtime it was created on shuts down. hyper's `Client` keeps its connection pool behind an
+    // `Arc`, so handing out clones of it lets requests reuse the same kept-alive connections, but
+    // those connections are driven by tasks on the runtime that opened them, so they can't be
+    // shared with other runtimes
+    static DEFAULT_CLIENT: RefCell<Option<(HttpsClient, Weak<()>)>> = RefCell::default();
+}
+
+/// Returns the `hyper::Client` to use for a request sent from this thread, creating a new one if
+/// there isn't one yet or the runtime the last one was made on has shut down.
+fn default_client() -> HttpsClient {
+    DEFAULT_CLIENT.with(|cached| {
+        let mut cached = cached.borrow_mut();
+        if let Some((ref client, ref alive)) = *cached {
+            if alive.upgrade().is_some() {
+                return client.clone();
+            }
+        }
+
+        let client = hyper::Client::builder().build(new_https_connector());
+        // outside a runtime there's nothing to tie the client to, so it's only used once
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let alive = Arc::new(());
+            *cached = Some((client.clone(), Arc::downgrade(&alive)));
+            // the runtime drops this task when it shuts down, taking the marker with it
+            runtime.spawn(async move {
+                let _alive = alive;
+                futures::future::pending::<()>().await
+            });
+        }
+        client
+    })
+}
+
+// n.b. this type is re-exported in the `raw` module - these docs are public!
+/// Settings for how egg-mode connects to Twitter.
+///
+/// These settings apply to every request egg-mode sends, across the whole process. To change
+/// them, assemble a `ConnectionConfig` and hand it to [`set_connection_config`]. Requests that
+/// have already started are not affected.
+///
+/// [`set_connection_config`]: fn.set_connection_config.html
+///
+/// By default, egg-mode creates a `hyper::Client` the first time it sends a request from a thread,
+/// and shares it between every request after that from the same thread, as long as the tokio
+/// runtime it was created on is still running. This lets requests reuse connections that were kept
+/// alive from earlier ones, instead of performing a new TLS handshake for every call, without
+/// handing one runtime a connection that belongs to another.
+///
+/// ## Providing your own client
+///
+/// If your application already keeps a `hyper::Client` around for its own connection pool, you can
+/// give it to egg-mode with `client`, and egg-mode will send its requests through that instead of
+/// creating its own. The client needs to use the same HTTPS connector type that egg-mode was
+/// compiled with, which is available as the [`HttpsConnector`] type alias. In practice, this means
+/// the client needs to be built from a `hyper_tls::HttpsConnector` if egg-mode is using the
+/// `native_tls` feature, or from a `hyper_rustls::HttpsConnector` if egg-mode is using `rustls` or
+/// `rustls_webpki`.
+///
+/// [`HttpsConnector`]: type.HttpsConnector.html
+///
+/// ```rust
+/// use egg_mode::raw::{self, ConnectionConfig};
+///
+/// # #[cfg(feature = "native_tls")] {
+/// let client = hyper::Client::builder().build(hyper_tls::HttpsConnector::new());
+///
+/// raw::set_connection_config(ConnectionConfig::new().client(client));
+/// # }
+/// ```
+///
+/// Any other [`HttpTransport`] can be set with `transport` instead, to send requests some other way.
+///
+/// [`HttpTransport`]: trait.HttpTransport.html
+///
+/// ## Testing without Twitter
+///
+/// Setting a [`MockTransport`] with `transport` answers every request egg-mode makes with canned
+/// responses, so code that uses egg-mode can be tested without connecting to Twitter. Since this
+/// setting applies to the whole process, tests that run in parallel should use [`with_transport`]
+/// instead, which only applies to the requests made by one future.
+///
+/// [`MockTransport`]: struct.MockTransport.html
+/// [`with_transport`]: fn.with_transport.html
+///
+/// ## Limiting response sizes
+///
+/// By default, egg-mode reads the whole body of every response it receives, no matter how large.
+/// If you'd rather not trust that, `max_body_bytes` sets a limit on how much it will read. Any
+/// response whose body goes over the limit is abandoned part-way through with
+/// `Error::BodyTooLarge`. This does not apply to the streaming API, whose responses never end.
+///
+/// ## Timeouts
+///
+/// By default, egg-mode waits as long as it takes for Twitter to respond, so a connection that hangs
+/// leaves its call waiting forever. `timeout` sets a limit on how long each request can take, from
+/// sending it to reading the last of its body, after which the call fails with `Error::Timeout`.
+/// Like `max_body_bytes`, this does not apply to the streaming API.
+///
+/// ## Identifying your application
+///
+/// egg-mode doesn't send a `User-Agent` header by default. If you'd like Twitter (or a proxy in
+/// between) to see which application is making requests, `user_agent` sets one for every request.
+/// Requests assembled with the `raw` module that already have a `User-Agent` header keep their own.
+///
+/// ## Tweet formats
+///
+/// There's no setting for which tweet format to request: egg-mode always asks for "extended"
+/// tweets, so `Tweet::text` holds the full text of every tweet instead of a version truncated to
+/// 140 characters.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionConfig {
+    transport: Option<Arc<dyn HttpTransport>>,
+    max_body_bytes: Option<usize>,
+    timeout: Option<Duration>,
+    user_agent: Option<HeaderValue>,
+}
+
+impl ConnectionConfig {
+    /// Creates a new `ConnectionConfig` with the default settings.
+    pub fn new() -> ConnectionConfig {
+        ConnectionConfig::default()
+    }
+
+    /// Sets the `hyper::Client` egg-mode sends its requests through.
+    pub fn client(self, client: HttpsClient) -> ConnectionConfig {
+        self.transport(client)
+    }
+
+    /// Sets the [`HttpTransport`] egg-mode sends its requests through.
+    ///
+    /// [`HttpTransport`]: trait.HttpTransport.html
+    pub fn transport<T: HttpTransport + 'static>(mut self, transport: T) -> ConnectionConfig {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets the largest response body, in bytes, that egg-mode will read before giving up with
+    /// `Error::BodyTooLarge`. By default there is no limit.
+    pub fn max_body_bytes(mut self, max: usize) -> ConnectionConfig {
+        self.max_body_bytes = Some(max);
+        self
+    }
+
+    /// Sets how long egg-mode will wait for a response, including reading its body, before giving
+    /// up with `Error::Timeout`. By default there is no limit.
+    pub fn timeout(mut self, timeout: Duration) -> ConnectionConfig {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header to send with every request.
+    ///
+    /// The header is given as a `HeaderValue`, so any characters that aren't allowed in an HTTP
+    /// header are caught when it's made, instead of when egg-mode sends a request:
+    ///
+    /// ```rust
+    /// use egg_mode::raw::{ConnectionConfig, HeaderValue};
+    ///
+    /// let config = ConnectionConfig::new().user_agent(HeaderValue::from_static("my-app/1.0"));
+    ///
+    /// assert!(HeaderValue::from_str("my-app\n1.0").is_err());
+    /// ```
+    pub fn user_agent(mut self, user_agent: HeaderValue) -> ConnectionConfig {
+        self.user_agent = Some(user_agent);
+        self
+    }
+}
+
+// n.b. this function is re-exported in the `raw` module - these docs are public!
+/// Replaces the settings egg-mode uses to connect to Twitter.
+///
+/// See the documentation for [`ConnectionConfig`] for details.
+///
+/// [`ConnectionConfig`]: struct.ConnectionConfig.html
+pub fn set_connection_config(config: ConnectionConfig) {
+    // a poisoned lock can only come from a panic while swapping the config, which leaves nothing
+    // half-written, so it's fine to keep using it
+    match CONNECTION_CONFIG.write() {
+        Ok(mut current) => *current = config,
+        Err(poisoned) => *poisoned.into_inner() = config,
+    }
+}
+
+/// Sends the given request with the current connection settings.
+pub(crate) fn send(mut request: Request<Body>) -> TransportFuture {
+    let config = CONNECTION_CONFIG
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(ref user_agent) = config.user_agent {
+        request
+            .headers_mut()
+            .entry(USER_AGENT)
+            .or_insert_with(|| user_agent.clone());
+    }
+
+    let scoped = SCOPED_TRANSPORT.with(|t| t.borrow().clone());
+    match scoped.as_ref().or_else(|| config.transport.as_ref()) {
+        Some(transport) => transport.send(request),
+        None => default_client().send(request),
+    }
+}
+
+/// Returns how long a request can take before it's abandoned, if a limit has been set.
+pub(crate) fn request_timeout() -> Option<Duration> {
+    CONNECTION_CONFIG
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .timeout
+}
+
+/// Returns the largest response body that should be read, if a limit has been set.
+pub(crate) fn max_body_bytes() -> Option<usize> {
+    CONNECTION_CONFIG
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .max_body_bytes
+}
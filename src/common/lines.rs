@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Helpers for reading newline-delimited JSON out of a chunked response body.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use hyper::Body;
+
+use crate::error::Result;
+
+/// A buffer that collects bytes from a chunked body and splits them into `\r\n`-delimited lines.
+///
+/// Chunk boundaries don't line up with line boundaries, so a line can arrive split across several
+/// chunks, and one chunk can hold several lines. Bytes are held here until their line is complete.
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    buf: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Adds the given bytes to the end of the buffer.
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Removes the first complete line from the buffer and returns it without its `\r\n`. Returns
+    /// `None` if the buffer doesn't hold a complete line yet.
+    pub(crate) fn next_line(&mut self) -> Option<Vec<u8>> {
+        let pos = self.buf.windows(2).position(|w| w == b"\r\n")?;
+        let mut line = self.buf.drain(..pos + 2).collect::<Vec<_>>();
+        line.truncate(pos);
+        Some(line)
+    }
+
+    /// Removes and returns whatever is left in the buffer, for when the body has ended without a
+    /// final `\r\n`.
+    pub(crate) fn take_rest(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
+}
+
+// n.b. this type is re-exported in the `raw` module - these docs are public!
+/// A `Stream` that reads newline-delimited JSON out of a response body.
+///
+/// Some endpoints, like the streaming API, send their response as a series of JSON values, each
+/// on its own line ending in `\r\n`, and keep the connection open to send more. This wraps the
+/// `Body` of such a response and yields each value as it's completed, holding on to partial lines
+/// until the rest of them arrive. Blank lines, which Twitter sends to keep the connection alive,
+/// are skipped. If the body ends with an unfinished line, it's parsed as a final value.
+///
+/// Each value is yielded as a `serde_json::Value`, so it can be deserialized into whatever type
+/// the endpoint sends.
+pub struct JsonLineDecoder {
+    body: Option<Body>,
+    lines: LineBuffer,
+}
+
+impl JsonLineDecoder {
+    /// Creates a new `JsonLineDecoder` that reads from the given body.
+    pub fn new(body: Body) -> JsonLineDecoder {
+        JsonLineDecoder {
+            body: Some(body),
+            lines: LineBuffer::default(),
+        }
+    }
+}
+
+/// Parses the given line as JSON, or returns `None` if it's blank.
+fn parse_line(line: &[u8]) -> Option<Result<serde_json::Value>> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        None
+    } else {
+        Some(serde_json::from_slice(line).map_err(Into::into))
+    }
+}
+
+impl Stream for JsonLineDecoder {
+    type Item = Result<serde_json::Value>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            while let Some(line) = self.lines.next_line() {
+                if let Some(value) = parse_line(&line) {
+                    return Poll::Ready(Some(value));
+                }
+            }
+
+            let body = match self.body.as_mut() {
+                Some(body) => body,
+                None => return Poll::Ready(None),
+            };
+
+            match Pin::new(body).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(chunk))) => self.lines.push(&chunk),
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => {
+                    self.body = None;
+                    let rest = self.lines.take_rest();
+                    return Poll::Ready(parse_line(&rest));
+                }
+            }
+        }
+    }
+}
@@ -87,6 +87,25 @@
 //! `rate_headers` is an infra function that takes the `Headers` and returns an empty `Response`
 //! with the rate-limit info parsed out. It's only exported for a couple functions in `list` which
 //! need to get that info even on an error.
+//!
+//! ## Connection settings
+//!
+//! The `connection` module holds `ConnectionConfig`, the process-wide settings for the HTTP client
+//! all requests go through, and `send`, which every web call uses to send its request with those
+//! settings. `send` adds the configured `User-Agent` header and hands the request to the
+//! transport set with `with_transport` or `ConnectionConfig::transport`, falling back to the
+//! shared `hyper::Client`. The module also holds the `HttpTransport` trait with `MockTransport`,
+//! the feature-dependent `HttpsConnector` and `HttpsClient` type aliases, and the compile errors for
+//! getting the TLS features wrong.
+//!
+//! `resolve_tco`, which follows a shortened link's redirects through `send`, lives in `entities`
+//! next to the URL entities it's meant for, and is re-exported here and at the crate root.
+//!
+//! ## `Backoff`
+//!
+//! `Backoff` is the public delay policy that everything which waits between attempts takes, be it
+//! retrying a rate-limited call, reconnecting a stream, or pacing a batch of actions. It lives in
+//! its own module and is re-exported at the crate root.
 
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -96,12 +115,20 @@ use std::pin::Pin;
 
 use hyper::header::{HeaderMap, HeaderValue};
 use percent_encoding::{utf8_percent_encode, AsciiSet, PercentEncode};
+use serde::{Deserialize, Serialize};
 
+mod backoff;
+mod connection;
+mod lines;
 mod response;
 
 pub use crate::auth::raw::{get, post, post_json};
 
+pub use crate::common::backoff::*;
+pub use crate::common::connection::*;
+pub use crate::common::lines::*;
 pub use crate::common::response::*;
+pub use crate::entities::resolve_tco;
 use crate::{error, list, user};
 
 /// Macro to create a `Serialize`/`Deserialize` implementation allowing for deserialization via the
@@ -291,7 +318,16 @@ pub type CowStr = Cow<'static, str>;
 ///     .extended_tweets()
 ///     .add_user_param("rustlang".into());
 /// ```
-#[derive(Debug, Clone, Default, derive_more::Deref, derive_more::DerefMut, derive_more::From)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    Deserialize,
+    Serialize,
+    derive_more::Deref,
+    derive_more::DerefMut,
+    derive_more::From,
+)]
 pub struct ParamList(HashMap<Cow<'static, str>, Cow<'static, str>>);
 
 impl ParamList {
@@ -567,6 +603,50 @@ pub(crate) mod tests {
         content
     }
 
+    /// Builds the JSON body of a page of tweets with the given IDs, each one a copy of the tweet in
+    /// `sample_payloads/sample-reply.json`.
+    pub(crate) fn tweet_page(ids: &[u64]) -> String {
+        let sample: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-reply.json")).unwrap();
+        let tweets = ids
+            .iter()
+            .map(|&id| {
+                let mut tweet = sample.clone();
+                tweet["id"] = id.into();
+                tweet["id_str"] = id.to_string().into();
+                tweet
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string(&tweets).unwrap()
+    }
+
+    /// Builds the JSON body of a list of users with the given IDs, each one a copy of the first user
+    /// in `sample_payloads/user_array.json` with its screen name set to `user<ID>`.
+    pub(crate) fn user_page(ids: &[u64]) -> String {
+        let sample: Vec<serde_json::Value> =
+            serde_json::from_str(&load_file("sample_payloads/user_array.json")).unwrap();
+        let users = ids
+            .iter()
+            .map(|&id| {
+                let mut user = sample[0].clone();
+                user["id"] = id.into();
+                user["id_str"] = id.to_string().into();
+                user["screen_name"] = format!("user{}", id).into();
+                user
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string(&users).unwrap()
+    }
+
+    /// Builds the rate-limit headers Twitter sends with a response.
+    pub(crate) fn rate_limit_headers(limit: i32, remaining: i32, reset: i64) -> Headers {
+        let mut headers = Headers::new();
+        headers.insert("x-rate-limit-limit", limit.into());
+        headers.insert("x-rate-limit-remaining", remaining.into());
+        headers.insert("x-rate-limit-reset", reset.into());
+        headers
+    }
+
     #[test]
     fn test_codepoints_to_bytes() {
         let unicode = "frônt Iñtërnâtiônàližætiøn ënd";
@@ -580,4 +660,736 @@ pub(crate) mod tests {
         codepoints_to_bytes(&mut range, unicode);
         assert_eq!(&unicode[range.0..range.1], "Iñtërnâtiônàližætiøn ënd");
     }
+
+    #[tokio::test]
+    async fn json_lines_across_chunks() {
+        use futures::TryStreamExt;
+
+        let chunks: Vec<Result<&'static str, std::io::Error>> = vec![
+            Ok("{\"id\": 1}\r\n{\"id\""),
+            Ok(": 2}\r\n\r\n{\"id\": 3}\r"),
+            Ok("\n{\"id\": 4}"),
+        ];
+        let body = hyper::Body::wrap_stream(futures::stream::iter(chunks));
+
+        let values = JsonLineDecoder::new(body)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let ids = values
+            .iter()
+            .map(|v| v["id"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn merge_rate_limits() {
+        let page = |remaining: i32, reset: i32, val: u32| {
+            Response::new(
+                RateLimit {
+                    limit: 15,
+                    remaining,
+                    reset,
+                },
+                val,
+            )
+        };
+        let pages = || {
+            vec![
+                page(3, 100, 1),
+                page(2, 100, 2),
+                page(14, 200, 3),
+                page(-1, -1, 4),
+            ]
+        };
+
+        let collected: Response<Vec<u32>> = pages().into_iter().collect();
+        assert_eq!(collected.response, vec![1, 2, 3, 4]);
+        assert_eq!(collected.rate_limit_status.remaining, 14);
+        assert_eq!(collected.rate_limit_status.reset, 200);
+
+        let min = Response::collect_with(pages(), MergePolicy::MinRemaining);
+        assert_eq!(min.rate_limit_status.remaining, 2);
+
+        let first = Response::collect_with(pages(), MergePolicy::First);
+        assert_eq!(first.rate_limit_status.remaining, 3);
+
+        let last = Response::collect_with(pages(), MergePolicy::Last);
+        assert_eq!(last.rate_limit_status.remaining, -1);
+
+        let empty = Response::<Vec<u32>>::collect_with(vec![], MergePolicy::First);
+        assert!(empty.response.is_empty());
+        assert_eq!(empty.rate_limit_status.reset, -1);
+    }
+
+    #[test]
+    fn merge_unknown_rate_limits() {
+        let known = |remaining: i32, reset: i32| RateLimit {
+            limit: 15,
+            remaining,
+            reset,
+        };
+        let unknown = RateLimit {
+            limit: -1,
+            remaining: -1,
+            reset: -1,
+        };
+        // a page that only had some of the headers
+        let partial = RateLimit {
+            limit: -1,
+            remaining: -1,
+            reset: 100,
+        };
+        let collect = |limits: Vec<RateLimit>, policy: MergePolicy| {
+            let pages = limits.into_iter().map(|rate| Response::new(rate, ()));
+            Response::collect_with(pages, policy).rate_limit_status
+        };
+
+        for &policy in &[MergePolicy::LatestReset, MergePolicy::MinRemaining] {
+            // unknown first page
+            let merged = collect(vec![unknown, known(5, 100), known(4, 100)], policy);
+            assert_eq!((merged.remaining, merged.reset), (4, 100), "{:?}", policy);
+
+            // unknown page between known ones, in the same window
+            let merged = collect(vec![known(5, 100), partial, known(4, 100)], policy);
+            assert_eq!((merged.remaining, merged.reset), (4, 100), "{:?}", policy);
+
+            // unknown last page
+            let merged = collect(vec![known(5, 100), known(4, 100), partial, unknown], policy);
+            assert_eq!((merged.remaining, merged.reset), (4, 100), "{:?}", policy);
+            assert!(merged.is_known());
+
+            // nothing known at all
+            let merged = collect(vec![unknown, unknown], policy);
+            assert!(!merged.is_known(), "{:?}", policy);
+            assert_eq!(merged.remaining, -1);
+        }
+
+        // the sentinels don't get in the way of picking the newer window
+        let merged = collect(
+            vec![known(1, 100), unknown, known(14, 200), partial],
+            MergePolicy::LatestReset,
+        );
+        assert_eq!((merged.remaining, merged.reset), (14, 200));
+        let merged = collect(
+            vec![known(1, 100), unknown, known(14, 200), partial],
+            MergePolicy::MinRemaining,
+        );
+        assert_eq!((merged.remaining, merged.reset), (1, 100));
+    }
+
+    #[test]
+    fn response_serde_roundtrip() {
+        use crate::tweet::Tweet;
+
+        let tweets: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        let resp = Response::new(
+            RateLimit {
+                limit: 900,
+                remaining: 899,
+                reset: 1600000000,
+            },
+            tweets,
+        );
+
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["remaining"], 899);
+        let loaded: Response<Vec<Tweet>> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), json);
+
+        assert_eq!(loaded.rate_limit_status.limit, 900);
+        assert_eq!(loaded.rate_limit_status.remaining, 899);
+        assert_eq!(loaded.rate_limit_status.reset, 1600000000);
+        let ids = |resp: &Response<Vec<Tweet>>| resp.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(&loaded), ids(&resp));
+    }
+
+    #[tokio::test]
+    async fn response_headers() {
+        use hyper::header::HeaderValue;
+        use hyper::StatusCode;
+
+        let mut headers = Headers::new();
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("14"));
+        headers.insert("x-response-time", HeaderValue::from_static("37"));
+        headers.insert("X-Connection-Hash", HeaderValue::from_static("abc123"));
+
+        let resp = Response::from_headers(&headers, 5).unwrap();
+        assert_eq!(resp.header("x-response-time"), Some("37"));
+        assert_eq!(resp.header("X-Connection-Hash"), Some("abc123"));
+        assert_eq!(resp.header("x-rate-limit-remaining"), Some("14"));
+        assert_eq!(resp.header("x-transaction"), None);
+        assert_eq!(resp.rate_limit_status.remaining, 14);
+
+        let mapped = Response::map(resp, |n| n * 2);
+        assert_eq!(mapped.header("x-response-time"), Some("37"));
+        assert_eq!(
+            Response::new(mapped.rate_limit_status, ()).header("x-response-time"),
+            None
+        );
+
+        let mock = MockTransport::new().respond(StatusCode::OK, headers, "{}");
+        let token = crate::auth::Token::Bearer("abc".to_string());
+        let req = get("https://api.twitter.com/1.1/x.json", &token, None);
+        let resp = with_transport(mock, request_with_json_response::<serde_json::Value>(req))
+            .await
+            .unwrap();
+        assert_eq!(resp.header("x-connection-hash"), Some("abc123"));
+    }
+
+    #[test]
+    fn try_map_response() {
+        let rate_limit_status = RateLimit {
+            limit: 180,
+            remaining: 179,
+            reset: 1_600_000_000,
+        };
+
+        let resp = Response::new(rate_limit_status, "1234");
+        let parsed = Response::try_map(resp, |s| s.parse::<u64>()).unwrap();
+        assert_eq!(parsed.response, 1234);
+        assert_eq!(parsed.rate_limit_status.limit, 180);
+        assert_eq!(parsed.rate_limit_status.remaining, 179);
+        assert_eq!(parsed.rate_limit_status.reset, 1_600_000_000);
+
+        let resp = Response::new(rate_limit_status, "not a number");
+        assert!(Response::try_map(resp, |s| s.parse::<u64>()).is_err());
+    }
+
+    #[test]
+    fn rate_limit_reset_time() {
+        use chrono::TimeZone;
+        use std::time::Duration;
+
+        let known = RateLimit {
+            limit: 15,
+            remaining: 0,
+            reset: 1_600_000_000,
+        };
+        let reset = chrono::Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+        assert_eq!(known.reset_time(), Some(reset));
+
+        let before = reset - chrono::Duration::seconds(90);
+        assert_eq!(known.reset_in(before), Some(Duration::from_secs(90)));
+        let after = reset + chrono::Duration::seconds(90);
+        assert_eq!(known.reset_in(after), Some(Duration::from_secs(0)));
+
+        let unknown = RateLimit {
+            limit: -1,
+            remaining: -1,
+            reset: -1,
+        };
+        assert_eq!(unknown.reset_time(), None);
+        assert_eq!(unknown.reset_in(reset), None);
+    }
+
+    #[test]
+    fn rate_limit_should_wait() {
+        let now = chrono::Utc::now().timestamp() as i32;
+        let limit = |remaining: i32, reset: i32| RateLimit {
+            limit: 15,
+            remaining,
+            reset,
+        };
+
+        assert!(limit(0, now + 600).is_known());
+        assert!(limit(0, now + 600).should_wait());
+        assert!(!limit(1, now + 600).should_wait());
+        assert!(!limit(0, now - 600).should_wait());
+
+        let unknown = RateLimit {
+            limit: -1,
+            remaining: -1,
+            reset: -1,
+        };
+        assert!(!unknown.is_known());
+        assert!(!unknown.should_wait());
+        assert!(!limit(0, -1).is_known());
+        assert!(!limit(0, -1).should_wait());
+    }
+
+    /// Starts a bare-bones HTTP/1.1 server on a local port that answers every request with the
+    /// given raw response, keeping connections open between requests. Returns its address and a
+    /// count of how many connections it has accepted.
+    pub(crate) fn serve(
+        response: Vec<u8>,
+    ) -> (
+        std::net::SocketAddr,
+        std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    ) {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let response = response.clone();
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                        if line == "\r\n" {
+                            stream.write_all(&response).unwrap();
+                        }
+                        line.clear();
+                    }
+                });
+            }
+        });
+
+        (addr, connections)
+    }
+
+    /// Starts a bare-bones HTTP/1.1 server like `serve`, but which answers requests with each of
+    /// the given raw responses in turn, repeating the last one once they run out. Returns its
+    /// address and the request line (like `GET /path?query HTTP/1.1`) of every request it has
+    /// received, in order.
+    pub(crate) fn serve_sequence(
+        responses: Vec<Vec<u8>>,
+    ) -> (
+        std::net::SocketAddr,
+        std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    ) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+        use std::sync::{Arc, Mutex};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let responses = Arc::new(responses);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let seen = seen.clone();
+                let responses = responses.clone();
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    let mut request_line = None;
+                    let mut body_len = 0;
+                    while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                        if line == "\r\n" {
+                            let mut body = vec![0; body_len];
+                            reader.read_exact(&mut body).unwrap();
+                            body_len = 0;
+                            let mut seen = seen.lock().unwrap();
+                            seen.push(request_line.take().unwrap_or_default());
+                            let idx = (seen.len() - 1).min(responses.len() - 1);
+                            stream.write_all(&responses[idx]).unwrap();
+                        } else if request_line.is_none() {
+                            request_line = Some(line.trim_end().to_string());
+                        } else if line.to_ascii_lowercase().starts_with("content-length:") {
+                            body_len = line["content-length:".len()..].trim().parse().unwrap_or(0);
+                        }
+                        line.clear();
+                    }
+                });
+            }
+        });
+
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn requests_share_connections() {
+        use std::sync::atomic::Ordering;
+
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}".to_vec();
+        let (addr, connections) = serve(response);
+
+        for _ in 0..2 {
+            let request = hyper::Request::get(format!("http://{}/", addr))
+                .body(hyper::Body::empty())
+                .unwrap();
+            let (_, body) = raw_request(request).await.unwrap();
+            assert_eq!(body, b"{}");
+            // give the connection a moment to go back into the pool
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn requests_from_two_runtimes() {
+        use std::sync::atomic::Ordering;
+        use std::time::Duration;
+
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}".to_vec();
+        let (addr, connections) = serve(response);
+        let fetch = move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let request = hyper::Request::get(format!("http://{}/", addr))
+                .body(hyper::Body::empty())
+                .unwrap();
+            let resp = runtime.block_on(async {
+                tokio::time::timeout(Duration::from_secs(5), raw_request(request)).await
+            });
+            (runtime, resp)
+        };
+
+        // the first runtime stays alive but sits idle, so a connection it opened can't be used by
+        // the second one
+        let (first, resp) = std::thread::spawn(fetch).join().unwrap();
+        assert_eq!(resp.unwrap().unwrap().1, b"{}");
+        let (_second, resp) = std::thread::spawn(fetch).join().unwrap();
+        assert_eq!(resp.unwrap().unwrap().1, b"{}");
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        // a runtime that has shut down makes way for a new one on the same thread
+        drop(first);
+        for count in 3..5 {
+            let (runtime, resp) = fetch();
+            assert_eq!(resp.unwrap().unwrap().1, b"{}");
+            drop(runtime);
+            assert_eq!(connections.load(Ordering::SeqCst), count);
+        }
+    }
+
+    #[tokio::test]
+    async fn bad_status_body() {
+        let not_found = "<html><body>Sorry, that page does not exist</body></html>";
+        let response = format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{}",
+            not_found.len(),
+            not_found
+        );
+        let (addr, _) = serve(response.into_bytes());
+
+        let request = hyper::Request::get(format!("http://{}/", addr))
+            .body(hyper::Body::empty())
+            .unwrap();
+        match raw_request(request).await {
+            Err(error::Error::BadStatus(status, body)) => {
+                assert_eq!(status, hyper::StatusCode::NOT_FOUND);
+                assert_eq!(body, not_found);
+            }
+            other => panic!("expected BadStatus, got {:?}", other),
+        }
+
+        let long_body = "x".repeat(10_000);
+        let response = format!(
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: {}\r\n\r\n{}",
+            long_body.len(),
+            long_body
+        );
+        let (addr, _) = serve(response.into_bytes());
+
+        let request = hyper::Request::get(format!("http://{}/", addr))
+            .body(hyper::Body::empty())
+            .unwrap();
+        match raw_request(request).await {
+            Err(error::Error::BadStatus(status, body)) => {
+                assert_eq!(status, hyper::StatusCode::FORBIDDEN);
+                assert_eq!(body.len(), 4096);
+            }
+            other => panic!("expected BadStatus, got {:?}", other),
+        }
+
+        // the cut backs off to the start of a character that straddles it
+        let straddling = format!("{}é and more", "x".repeat(4095));
+        let mock =
+            MockTransport::new().respond(hyper::StatusCode::FORBIDDEN, Headers::new(), straddling);
+        let request = hyper::Request::get("https://api.twitter.com/1.1/")
+            .body(hyper::Body::empty())
+            .unwrap();
+        match with_transport(mock, raw_request(request)).await {
+            Err(error::Error::BadStatus(_, body)) => assert_eq!(body, "x".repeat(4095)),
+            other => panic!("expected BadStatus, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn user_error_codes() {
+        let respond = |code: i32, message: &str| {
+            let body = format!(
+                r#"{{"errors":[{{"code":{},"message":"{}"}}]}}"#,
+                code, message
+            );
+            let response = format!(
+                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let (addr, _) = serve(response.into_bytes());
+            hyper::Request::get(format!("http://{}/", addr))
+                .body(hyper::Body::empty())
+                .unwrap()
+        };
+
+        match raw_request(respond(50, "User not found.")).await {
+            Err(error::Error::UserNotFound(message)) => assert_eq!(message, "User not found."),
+            other => panic!("expected UserNotFound, got {:?}", other),
+        }
+
+        match raw_request(respond(63, "User has been suspended.")).await {
+            Err(error::Error::UserSuspended(message)) => {
+                assert_eq!(message, "User has been suspended.")
+            }
+            other => panic!("expected UserSuspended, got {:?}", other),
+        }
+
+        match raw_request(respond(34, "Sorry, that page does not exist.")).await {
+            Err(error::Error::TwitterError(_, errors)) => {
+                assert_eq!(errors.errors[0].code, 34);
+                assert_eq!(errors.errors[0].message, "Sorry, that page does not exist.");
+            }
+            other => panic!("expected TwitterError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_transport() {
+        use hyper::header::{HeaderValue, AUTHORIZATION};
+        use hyper::{Method, StatusCode};
+
+        let user = serde_json::from_str::<Vec<serde_json::Value>>(&load_file(
+            "sample_payloads/user_array.json",
+        ))
+        .unwrap()
+        .remove(3);
+        let mut headers = Headers::new();
+        headers.insert("x-rate-limit-limit", HeaderValue::from_static("900"));
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("899"));
+        headers.insert("x-rate-limit-reset", HeaderValue::from_static("1600000000"));
+        let mock = MockTransport::new().respond(StatusCode::OK, headers, user.to_string());
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let shown = with_transport(mock.clone(), user::show("rustlang", &token))
+            .await
+            .unwrap();
+        assert_eq!(shown.id, 165262228);
+        assert_eq!(shown.screen_name, "rustlang");
+        assert_eq!(shown.rate_limit_status.remaining, 899);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].uri.host(), Some("api.twitter.com"));
+        assert_eq!(requests[0].uri.path(), "/1.1/users/show.json");
+        let query = requests[0].uri.query().unwrap();
+        assert!(query.split('&').any(|p| p == "screen_name=rustlang"));
+        assert!(query.split('&').any(|p| p == "tweet_mode=extended"));
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Bearer abc");
+
+        // errors from the mock go through the same checks as ones from Twitter
+        let mock = MockTransport::new().respond(
+            StatusCode::NOT_FOUND,
+            Headers::new(),
+            r#"{"errors":[{"code":50,"message":"User not found."}]}"#,
+        );
+        match with_transport(mock, user::show("nobody", &token)).await {
+            Err(error::Error::UserNotFound(_)) => (),
+            other => panic!("expected UserNotFound, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn auto_retry_rate_limit() {
+        use std::cell::Cell;
+        use std::time::{Duration, Instant};
+
+        // a reset time in the past, so the retries only wait for the backoff
+        let reset = chrono::Utc::now().timestamp() as i32 - 10;
+        let backoff = || Backoff::linear(Duration::from_millis(50), Duration::from_secs(1));
+
+        let calls = Cell::new(0);
+        let res = auto_retry(3, backoff(), || {
+            calls.set(calls.get() + 1);
+            let call = calls.get();
+            async move {
+                if call == 1 {
+                    Err(error::Error::RateLimit(reset))
+                } else {
+                    Ok(call)
+                }
+            }
+        })
+        .await;
+        assert_eq!(res.unwrap(), 2);
+
+        // two retries wait 50ms and then 100ms
+        let calls = Cell::new(0);
+        let start = Instant::now();
+        let res: error::Result<()> = auto_retry(2, backoff(), || {
+            calls.set(calls.get() + 1);
+            async move { Err(error::Error::RateLimit(reset)) }
+        })
+        .await;
+        assert!(matches!(res, Err(error::Error::RateLimit(_))));
+        assert_eq!(calls.get(), 3);
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        let calls = Cell::new(0);
+        let res: error::Result<()> = auto_retry(2, backoff(), || {
+            calls.set(calls.get() + 1);
+            async move { Err(error::Error::MissingValue("id")) }
+        })
+        .await;
+        assert!(matches!(res, Err(error::Error::MissingValue("id"))));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_transient_errors() {
+        use hyper::StatusCode;
+        use std::time::Duration;
+
+        let backoff =
+            || Backoff::exponential(Duration::from_millis(1), 2, Duration::from_millis(4));
+        let token = crate::auth::Token::Bearer("abc".to_string());
+        let show = |mock: MockTransport| {
+            let token = &token;
+            retry_transient(2, backoff(), move || {
+                with_transport(mock.clone(), user::show("rustlang", token))
+            })
+        };
+        let user = serde_json::from_str::<Vec<serde_json::Value>>(&load_file(
+            "sample_payloads/user_array.json",
+        ))
+        .unwrap()
+        .remove(3)
+        .to_string();
+
+        // one transient failure, then success
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Headers::new(),
+                "over capacity",
+            )
+            .respond(StatusCode::OK, Headers::new(), user.clone());
+        let shown = show(mock.clone()).await.unwrap();
+        assert_eq!(shown.screen_name, "rustlang");
+        assert_eq!(mock.requests().len(), 2);
+
+        // a failure that doesn't go away runs out of retries
+        let mock =
+            MockTransport::new().respond(StatusCode::SERVICE_UNAVAILABLE, Headers::new(), "down");
+        match show(mock.clone()).await {
+            Err(error::Error::BadStatus(status, _)) => {
+                assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE)
+            }
+            other => panic!("expected BadStatus, got {:?}", other),
+        }
+        assert_eq!(mock.requests().len(), 3);
+
+        // other errors aren't retried
+        let mock = MockTransport::new()
+            .respond(StatusCode::FORBIDDEN, Headers::new(), "forbidden")
+            .respond(StatusCode::OK, Headers::new(), user);
+        match show(mock.clone()).await {
+            Err(error::Error::BadStatus(status, _)) => assert_eq!(status, StatusCode::FORBIDDEN),
+            other => panic!("expected BadStatus, got {:?}", other),
+        }
+        assert_eq!(mock.requests().len(), 1);
+
+        let calls = std::cell::Cell::new(0);
+        let res: error::Result<()> = retry_transient(2, backoff(), || {
+            calls.set(calls.get() + 1);
+            async move { Err(error::Error::RateLimit(0)) }
+        })
+        .await;
+        assert!(matches!(res, Err(error::Error::RateLimit(0))));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn request_timeout() {
+        use std::time::Duration;
+
+        // a transport that never answers, like a connection that hangs
+        #[derive(Debug)]
+        struct Hang;
+        impl HttpTransport for Hang {
+            fn send(&self, _: hyper::Request<hyper::Body>) -> TransportFuture {
+                Box::pin(futures::future::pending())
+            }
+        }
+
+        let limit = Duration::from_millis(10);
+        let request = hyper::Request::get("https://api.twitter.com/1.1/users/show.json")
+            .body(hyper::Body::empty())
+            .unwrap();
+        match with_transport(Hang, fetch_within(request, Some(limit))).await {
+            Err(error::Error::Timeout(d)) => assert_eq!(d, limit),
+            Err(e) => panic!("expected a timeout, got {:?}", e),
+            Ok(_) => panic!("expected a timeout, got a response"),
+        }
+
+        // responses that arrive in time are unaffected
+        let mock = MockTransport::new().respond(hyper::StatusCode::OK, Headers::new(), "{}");
+        let request = hyper::Request::get("https://api.twitter.com/1.1/users/show.json")
+            .body(hyper::Body::empty())
+            .unwrap();
+        let (parts, body) =
+            with_transport(mock, fetch_within(request, Some(Duration::from_secs(5))))
+                .await
+                .unwrap();
+        assert_eq!(parts.status, hyper::StatusCode::OK);
+        assert_eq!(body, b"{}");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn compressed_responses() {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let json = load_file("sample_payloads/tweet_array.json");
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(json.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+
+        let mut deflate = ZlibEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(json.as_bytes()).unwrap();
+        let deflate = deflate.finish().unwrap();
+
+        let load = |encoding: &str, body: &[u8]| {
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Encoding: {}\r\nContent-Length: {}\r\n\r\n",
+                encoding,
+                body.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(body);
+            let (addr, _) = serve(response);
+
+            let request = hyper::Request::get(format!("http://{}/", addr))
+                .body(hyper::Body::empty())
+                .unwrap();
+            raw_request(request)
+        };
+
+        let (_, body) = load("gzip", &gzip).await.unwrap();
+        assert_eq!(body, json.as_bytes());
+
+        let (_, body) = load("deflate", &deflate).await.unwrap();
+        assert_eq!(body, json.as_bytes());
+
+        match load("gzip", b"this isn't gzip").await {
+            Err(error::Error::IOError(e)) => assert!(matches!(
+                e.kind(),
+                std::io::ErrorKind::InvalidInput | std::io::ErrorKind::InvalidData
+            )),
+            other => panic!("expected IOError, got {:?}", other.map(|_| ())),
+        }
+    }
 }
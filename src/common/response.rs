@@ -4,18 +4,49 @@
 use crate::error::Error::{self, *};
 use crate::error::{Result, TwitterErrors};
 
-use hyper::client::{HttpConnector, ResponseFuture};
+use chrono::TimeZone;
+use hyper::body::HttpBody;
+#[cfg(feature = "gzip")]
+use hyper::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING};
 use hyper::{self, Body, Request};
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::time::Duration;
 
-use super::Headers;
+use super::{max_body_bytes, request_timeout, send, Backoff, Headers, TransportFuture};
 
 const X_RATE_LIMIT_LIMIT: &str = "X-Rate-Limit-Limit";
 const X_RATE_LIMIT_REMAINING: &str = "X-Rate-Limit-Remaining";
 const X_RATE_LIMIT_RESET: &str = "X-Rate-Limit-Reset";
 
+/// The Twitter error code for a tweet that duplicates one the user already posted.
+const DUPLICATE_STATUS_CODE: i32 = 187;
+
+/// The most bytes of an error response's body to keep in `BadStatus`.
+const MAX_ERROR_BODY_BYTES: usize = 4096;
+
+/// Twitter error codes that mean the credentials used to sign a request were rejected.
+const AUTH_ERROR_CODES: &[i32] = &[32, 89, 99, 135];
+
+/// The Twitter error code for an account that doesn't exist.
+const USER_NOT_FOUND_CODE: i32 = 50;
+
+/// The Twitter error code for an account that has been suspended.
+const USER_SUSPENDED_CODE: i32 = 63;
+
+/// Marker placed in a request's extensions when the response body shouldn't be checked for Twitter
+/// error objects. Set with `RequestBuilder::parse_twitter_errors`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SkipTwitterErrors;
+
+/// The OAuth signature base string used to sign a request. With the `debug_signatures` feature on,
+/// this is attached to signed requests so it can be printed if Twitter rejects the signature.
+#[derive(Debug, Clone)]
+pub(crate) struct SignatureBaseString(pub String);
+
 fn rate_limit(headers: &Headers, header: &'static str) -> Result<Option<i32>> {
     let val = headers.get(header);
 
@@ -48,9 +79,13 @@ fn rate_limit_reset(headers: &Headers) -> Result<Option<i32>> {
 ///
 ///As this implements `Deref` and `DerefMut`, you can transparently use the contained `response`'s
 ///methods as if they were methods on this struct.
-#[derive(
-    Debug, Deserialize, derive_more::Constructor, derive_more::Deref, derive_more::DerefMut,
-)]
+///
+///A `Response` can be saved with serde and loaded again later, for example to cache it. The
+///rate-limit fields are written next to a `response` field that holds the data.
+///
+///Other headers Twitter sent with the response, like `x-response-time` or `x-connection-hash`, can
+///be read with `header`, which is handy for debugging or for reporting a problem to Twitter.
+#[derive(Debug, Deserialize, Serialize, derive_more::Deref, derive_more::DerefMut)]
 pub struct Response<T> {
     /// The latest rate-limit information returned with the request.
     #[serde(flatten)]
@@ -60,9 +95,56 @@ pub struct Response<T> {
     #[deref_mut]
     #[serde(default)]
     pub response: T,
+    /// All the headers returned with the response, keyed by their lowercase names.
+    ///
+    /// This is `None` for a `Response` that wasn't loaded from a single call to Twitter, such as
+    /// one assembled with `Response::new`, or one that combines the results of several calls like
+    /// `user::profile_with_tweets`. Headers whose values aren't valid UTF-8 are left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_headers: Option<HashMap<String, String>>,
 }
 
 impl<T> Response<T> {
+    ///Creates a new `Response` with the given rate-limit information and contents, and no
+    ///`extra_headers`.
+    pub fn new(rate_limit_status: RateLimit, response: T) -> Response<T> {
+        Response {
+            rate_limit_status,
+            response,
+            extra_headers: None,
+        }
+    }
+
+    ///Creates a new `Response` with the given contents, and the rate-limit information and other
+    ///headers from the given response headers.
+    pub(crate) fn from_headers(headers: &Headers, response: T) -> Result<Response<T>> {
+        let mut extra_headers = HashMap::with_capacity(headers.len());
+        for (name, value) in headers {
+            if let Ok(value) = value.to_str() {
+                extra_headers
+                    .entry(name.as_str().to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+
+        Ok(Response {
+            rate_limit_status: RateLimit::try_from(headers)?,
+            response,
+            extra_headers: Some(extra_headers),
+        })
+    }
+
+    ///Returns the value of the given header from the response, if Twitter sent it.
+    ///
+    ///Header names are matched without regard to case. If Twitter sent the header more than once,
+    ///this returns the first value.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.extra_headers
+            .as_ref()?
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
     ///Convert a `Response<T>` to a `Response<U>` by running its contained response through the
     ///given function. This preserves its rate-limit information.
     ///
@@ -75,6 +157,7 @@ impl<T> Response<T> {
         Response {
             rate_limit_status: src.rate_limit_status,
             response: fun(src.response),
+            extra_headers: src.extra_headers,
         }
     }
 
@@ -91,6 +174,7 @@ impl<T> Response<T> {
         Ok(Response {
             rate_limit_status: src.rate_limit_status,
             response: fun(src.response)?,
+            extra_headers: src.extra_headers,
         })
     }
 
@@ -108,6 +192,134 @@ impl<T> Response<T> {
         Response {
             rate_limit_status: src.rate_limit_status,
             response: src.response.into(),
+            extra_headers: src.extra_headers,
+        }
+    }
+
+    /// Borrows the contained response of a `&Response<T>`, creating a `Response<&T>` with a copy
+    /// of its rate-limit information.
+    ///
+    /// This is useful for handing a response to something that only needs to read it, without
+    /// giving up the original. Like `map` and `into`, this is a type function rather than a member
+    /// function, so that it doesn't hide an `as_ref` method on the contained `T`.
+    pub fn as_ref(src: &Self) -> Response<&T> {
+        Response {
+            rate_limit_status: src.rate_limit_status,
+            response: &src.response,
+            extra_headers: src.extra_headers.clone(),
+        }
+    }
+}
+
+impl<T> Response<Vec<T>> {
+    /// Collects the given responses into one `Response<Vec<T>>`, using the given policy to decide
+    /// which rate-limit information to keep.
+    ///
+    /// This is like `collect`ing the responses with the `FromIterator` implementation, which uses
+    /// `MergePolicy::LatestReset`, but lets you pick the policy. See [`MergePolicy`] for the
+    /// choices. If there are no responses, every field of the rate-limit information is `-1`. The
+    /// `extra_headers` are taken from the last response that had any.
+    ///
+    /// [`MergePolicy`]: enum.MergePolicy.html
+    pub fn collect_with<I>(iter: I, policy: MergePolicy) -> Response<Vec<T>>
+    where
+        I: IntoIterator<Item = Response<T>>,
+    {
+        let mut rate_limit_status: Option<RateLimit> = None;
+        let mut extra_headers = None;
+        let mut response = Vec::new();
+
+        for item in iter {
+            let next = item.rate_limit_status;
+            rate_limit_status = Some(match rate_limit_status {
+                None => next,
+                Some(current) => policy.merge(current, next),
+            });
+            if item.extra_headers.is_some() {
+                extra_headers = item.extra_headers;
+            }
+            response.push(item.response);
+        }
+
+        Response {
+            rate_limit_status: rate_limit_status.unwrap_or(RateLimit {
+                limit: -1,
+                remaining: -1,
+                reset: -1,
+            }),
+            response,
+            extra_headers,
+        }
+    }
+}
+
+/// Collects a series of responses into one, keeping the rate-limit information according to
+/// `MergePolicy::LatestReset`. To use a different policy, use `Response::collect_with`.
+impl<T> FromIterator<Response<T>> for Response<Vec<T>> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Response<T>>,
+    {
+        Response::collect_with(iter, MergePolicy::LatestReset)
+    }
+}
+
+// n.b. this type is re-exported at the crate root - these docs are public!
+/// How to combine the rate-limit information of several responses into one.
+///
+/// This is used by `Response::collect_with`, when collecting several responses (for example, the
+/// pages of a paged call) into one `Response`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the rate-limit information with the latest `reset` time, since that's the most recent
+    /// window. If two responses share a window, keep the one with fewer calls `remaining`, since
+    /// it was received later. This is what the `FromIterator` implementation uses.
+    ///
+    /// Responses that didn't come with rate-limit information are ignored, unless none of them
+    /// did.
+    LatestReset,
+    /// Keep the rate-limit information with the fewest calls `remaining`, no matter which window
+    /// it's from. Like with `LatestReset`, responses that didn't come with rate-limit information
+    /// are ignored, unless none of them did.
+    MinRemaining,
+    /// Keep the rate-limit information from the first response.
+    First,
+    /// Keep the rate-limit information from the last response.
+    Last,
+}
+
+impl MergePolicy {
+    fn merge(self, current: RateLimit, next: RateLimit) -> RateLimit {
+        match self {
+            // a response without rate-limit information says nothing about the window, so it
+            // shouldn't replace one that has it
+            MergePolicy::LatestReset | MergePolicy::MinRemaining
+                if current.is_known() != next.is_known() =>
+            {
+                if next.is_known() {
+                    next
+                } else {
+                    current
+                }
+            }
+            MergePolicy::LatestReset => {
+                if (next.reset, -next.remaining) > (current.reset, -current.remaining) {
+                    next
+                } else {
+                    current
+                }
+            }
+            MergePolicy::MinRemaining => {
+                if current.remaining == -1
+                    || (next.remaining != -1 && next.remaining < current.remaining)
+                {
+                    next
+                } else {
+                    current
+                }
+            }
+            MergePolicy::First => current,
+            MergePolicy::Last => next,
         }
     }
 }
@@ -139,94 +351,325 @@ impl<T: Iterator> Iterator for ResponseIter<T> {
         Some(Response {
             rate_limit_status: self.it.rate_limit_status,
             response: self.it.response.next()?,
+            extra_headers: self.it.extra_headers.clone(),
         })
     }
 }
 
-#[cfg(not(any(feature = "native_tls", feature = "rustls", feature = "rustls_webpki")))]
-compile_error!(
-    "Crate `egg_mode` must be compiled with exactly one of the three \
-feature flags `native_tls`, `rustls` or `rustls_webpki` enabled, you attempted to \
-compile `egg_mode` with none of them enabled"
-);
-
-#[cfg(any(
-    all(
-        feature = "native_tls",
-        any(feature = "rustls", feature = "rustls_webpki")
-    ),
-    all(
-        feature = "rustls",
-        any(feature = "native_tls", feature = "rustls_webpki")
-    ),
-    all(
-        feature = "rustls_webpki",
-        any(feature = "native_tls", feature = "rustls")
-    ),
-))]
-compile_error!(
-    "features `egg_mode/native_tls`, `egg_mode/rustls` and \
-`egg_mode/rustls_webpki` are mutually exclusive, you attempted to compile `egg_mode` \
-with more than one of these feature flags enabled at the same time"
-);
-
-#[cfg(feature = "native_tls")]
-fn new_https_connector() -> hyper_tls::HttpsConnector<HttpConnector> {
-    hyper_tls::HttpsConnector::new()
+// n.b. this function is re-exported at the crate root - these docs are public!
+/// Run the given call, waiting for the rate limit to reset and trying again if Twitter says it's
+/// been reached.
+///
+/// Any loop that keeps calling an endpoint will eventually run into its rate limit, at which point
+/// the call fails with `Error::RateLimit`, holding the time the rate window resets. This function
+/// handles that case: when `make_call` fails with `RateLimit`, it sleeps until the given reset
+/// time, then calls `make_call` again to start a fresh request. It does this at most
+/// `max_retries` times, after which the `RateLimit` error is returned. Any other error is
+/// returned right away without retrying.
+///
+/// Before each retry, this also takes the next delay from `backoff`, and waits for that instead if
+/// it's longer than the wait for the reset. This keeps the retries from going out all at once when
+/// the reset time has already passed, as happens when the local clock is behind Twitter's.
+///
+/// Since the rate window can be up to 15 minutes long, this can take a while to finish. If you'd
+/// rather stop waiting after some time, wrap the returned future in a timeout.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// use std::time::Duration;
+/// use egg_mode::Backoff;
+///
+/// let backoff = Backoff::linear(Duration::from_secs(1), Duration::from_secs(5));
+/// let user = egg_mode::auto_retry(3, backoff, || egg_mode::user::show("rustlang", &token))
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub async fn auto_retry<F, Fut, T>(
+    max_retries: u32,
+    mut backoff: Backoff,
+    mut make_call: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match make_call().await {
+            Err(RateLimit(reset)) if retries < max_retries => {
+                retries += 1;
+                let delay = backoff.next_delay();
+                let wait = until_reset(reset, chrono::Utc::now().timestamp())
+                    .map_or(delay, |wait| wait.max(delay));
+                tokio::time::sleep(wait).await;
+            }
+            res => return res,
+        }
+    }
 }
 
-#[cfg(feature = "rustls")]
-fn new_https_connector() -> hyper_rustls::HttpsConnector<HttpConnector> {
-    hyper_rustls::HttpsConnector::with_native_roots()
+// n.b. this function is re-exported at the crate root - these docs are public!
+/// Run the given call, trying again after a delay if it fails in a way that's likely to be
+/// temporary.
+///
+/// Twitter occasionally answers with `502 Bad Gateway`, `503 Service Unavailable`, or `504 Gateway
+/// Timeout` when it's overloaded, and connections can drop partway through a request. Calling
+/// again after a moment usually works. This function does that: when `make_call` fails with
+/// `Error::BadStatus` for one of those three statuses, with an `Error::TwitterError` saying Twitter
+/// is over capacity or had an internal error (codes 130 and 131), or with an `Error::NetError` for
+/// a connection that couldn't be made or was cut off, it waits for the next delay from `backoff`,
+/// then calls `make_call` again to start a fresh request. It does this at most `max_retries`
+/// times, after which the last error is returned.
+///
+/// Any other error, such as a `4xx` status, an error from Twitter, `Error::RateLimit`, or a
+/// response that couldn't be parsed, is returned right away without retrying. To also wait out
+/// rate limits, combine this with `auto_retry`, or use `Error::is_retryable` to write your own
+/// retry loop.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// use std::time::Duration;
+/// use egg_mode::Backoff;
+///
+/// let backoff = Backoff::exponential(Duration::from_secs(1), 2, Duration::from_secs(30)).jitter(20);
+/// let user = egg_mode::retry_transient(3, backoff, || egg_mode::user::show("rustlang", &token))
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub async fn retry_transient<F, Fut, T>(
+    max_retries: u32,
+    mut backoff: Backoff,
+    mut make_call: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match make_call().await {
+            Err(ref e) if retries < max_retries && e.is_transient() => {
+                retries += 1;
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+            res => return res,
+        }
+    }
 }
 
-#[cfg(feature = "rustls_webpki")]
-fn new_https_connector() -> hyper_rustls::HttpsConnector<HttpConnector> {
-    hyper_rustls::HttpsConnector::with_webpki_roots()
+/// Returns how long to wait, starting at the Unix timestamp `now`, before the rate window ending at
+/// `reset` has passed, or `None` if it already has.
+pub(crate) fn until_reset(reset: i32, now: i64) -> Option<Duration> {
+    // the reset time only has a resolution of one second, so wait an extra second to make sure the
+    // window has actually reset by the time the call is made again
+    let wait = i64::from(reset) - now + 1;
+    if wait > 0 {
+        Some(Duration::from_secs(wait as u64))
+    } else {
+        None
+    }
 }
 
 // n.b. this function is re-exported in the `raw` module - these docs are public!
-/// Converts the given request into a raw `ResponseFuture` from hyper.
-pub fn get_response(request: Request<Body>) -> ResponseFuture {
-    let connector = new_https_connector();
-    let client = hyper::Client::builder().build(connector);
-    client.request(request)
+/// Starts sending the given request, returning a future that resolves to the raw response from
+/// hyper.
+pub fn get_response(request: Request<Body>) -> TransportFuture {
+    send(request)
+}
+
+/// Sends the given request and reads the whole response, giving up with `Error::Timeout` if that
+/// takes longer than `timeout`.
+pub(crate) async fn fetch_within(
+    request: Request<Body>,
+    timeout: Option<Duration>,
+) -> Result<(hyper::http::response::Parts, Vec<u8>)> {
+    let fetch = async {
+        let resp = send(request).await?;
+        let (parts, body) = resp.into_parts();
+        let body = read_body(body).await?;
+        Ok((parts, body))
+    };
+
+    match timeout {
+        Some(limit) => match tokio::time::timeout(limit, fetch).await {
+            Ok(res) => res,
+            Err(_) => Err(Timeout(limit)),
+        },
+        None => fetch.await,
+    }
 }
 
 // n.b. this function is re-exported in the `raw` module - these docs are public!
 /// Loads the given request, parses the headers and response for potential errors given by Twitter,
 /// and returns the headers and raw bytes returned from the response.
+///
+/// If the request was built with `parse_twitter_errors(false)`, the response body is not checked
+/// for Twitter errors, and only the HTTP status is used to detect an error.
+///
+/// With the `gzip` feature on, this asks for a compressed response (unless the request already has
+/// an `Accept-Encoding` header) and returns the body decompressed.
+///
+/// If a timeout was set with `ConnectionConfig::timeout`, this fails with `Error::Timeout` when the
+/// response hasn't been fully read by then.
 pub async fn raw_request(request: Request<Body>) -> Result<(Headers, Vec<u8>)> {
-    let connector = new_https_connector();
-    let client = hyper::Client::builder().build(connector);
-    let resp = client.request(request).await?;
-    let (parts, body) = resp.into_parts();
-    let body: Vec<_> = hyper::body::to_bytes(body).await?.to_vec();
-    if let Ok(errors) = serde_json::from_slice::<TwitterErrors>(&body) {
+    let parse_errors = request.extensions().get::<SkipTwitterErrors>().is_none();
+    let base_str = request.extensions().get::<SignatureBaseString>().cloned();
+    #[cfg(feature = "gzip")]
+    let request = accept_compressed(request);
+    let (parts, body) = fetch_within(request, request_timeout()).await?;
+    #[cfg(feature = "gzip")]
+    let body = decompress_body(&parts.headers, body)?;
+    let errors = if parse_errors {
+        serde_json::from_slice::<TwitterErrors>(&body).ok()
+    } else {
+        None
+    };
+    if let Some(errors) = errors {
         if errors.errors.iter().any(|e| e.code == 88)
             && parts.headers.contains_key(X_RATE_LIMIT_RESET)
         {
             return Err(RateLimit(rate_limit_reset(&parts.headers)?.unwrap()));
+        } else if errors
+            .errors
+            .iter()
+            .any(|e| e.code == DUPLICATE_STATUS_CODE)
+        {
+            return Err(DuplicateStatus(None));
+        } else if let Some(err) = errors
+            .errors
+            .iter()
+            .find(|e| AUTH_ERROR_CODES.contains(&e.code))
+        {
+            if err.code == 32 && cfg!(feature = "debug_signatures") {
+                if let Some(SignatureBaseString(base_str)) = base_str {
+                    eprintln!(
+                        "egg-mode: Twitter could not authenticate a request (code 32); \
+                         its signature base string was: {}",
+                        base_str
+                    );
+                }
+            }
+            return Err(Unauthorized {
+                code: err.code,
+                message: err.message.clone(),
+            });
+        } else if let Some(err) = errors.errors.iter().find(|e| e.code == USER_NOT_FOUND_CODE) {
+            return Err(UserNotFound(err.message.clone()));
+        } else if let Some(err) = errors.errors.iter().find(|e| e.code == USER_SUSPENDED_CODE) {
+            return Err(UserSuspended(err.message.clone()));
         } else {
             return Err(TwitterError(parts.headers, errors));
         }
     }
+    if parts.status == hyper::StatusCode::UNAUTHORIZED {
+        return Err(Unauthorized {
+            code: -1,
+            message: parts.status.to_string(),
+        });
+    }
     if !parts.status.is_success() {
-        return Err(BadStatus(parts.status));
+        return Err(BadStatus(parts.status, error_body(&body)));
     }
     Ok((parts.headers, body))
 }
 
+/// Asks for the response to the given request to be compressed, unless it already says which
+/// encodings it accepts.
+#[cfg(feature = "gzip")]
+fn accept_compressed(mut request: Request<Body>) -> Request<Body> {
+    request
+        .headers_mut()
+        .entry(ACCEPT_ENCODING)
+        .or_insert_with(|| HeaderValue::from_static("gzip, deflate"));
+    request
+}
+
+/// Decompresses the given response body according to its `Content-Encoding` header. Bodies
+/// without a `Content-Encoding`, or with one other than `gzip` or `deflate`, are returned as-is.
+///
+/// A body that isn't a valid gzip or deflate stream gives an `IOError` of kind `InvalidInput` or
+/// `InvalidData`. If a limit has been set with `ConnectionConfig::max_body_bytes`, it applies to
+/// the decompressed body as well.
+#[cfg(feature = "gzip")]
+fn decompress_body(headers: &Headers, body: Vec<u8>) -> Result<Vec<u8>> {
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    let encoding = match headers.get(CONTENT_ENCODING) {
+        Some(encoding) => encoding.to_str()?.trim().to_ascii_lowercase(),
+        None => return Ok(body),
+    };
+    let mut decoder: Box<dyn Read + '_> = match encoding.as_str() {
+        "gzip" | "x-gzip" => Box::new(GzDecoder::new(&body[..])),
+        // the "deflate" encoding in HTTP is a zlib stream, not a raw deflate stream
+        "deflate" => Box::new(ZlibDecoder::new(&body[..])),
+        _ => return Ok(body),
+    };
+
+    let mut decompressed = Vec::new();
+    match max_body_bytes() {
+        Some(max) => {
+            decoder
+                .take(max as u64 + 1)
+                .read_to_end(&mut decompressed)?;
+            if decompressed.len() > max {
+                return Err(BodyTooLarge(max));
+            }
+        }
+        None => {
+            decoder.read_to_end(&mut decompressed)?;
+        }
+    }
+
+    Ok(decompressed)
+}
+
+/// Converts the body of an error response into a `String` to report in `BadStatus`, keeping at
+/// most its first `MAX_ERROR_BODY_BYTES` bytes.
+fn error_body(body: &[u8]) -> String {
+    // a UTF-8 character is at most four bytes, so this keeps every character that starts before the
+    // cut whole, instead of turning one that straddles it into U+FFFD
+    let body = String::from_utf8_lossy(&body[..body.len().min(MAX_ERROR_BODY_BYTES + 3)]);
+    let mut end = body.len().min(MAX_ERROR_BODY_BYTES);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    body[..end].to_string()
+}
+
+/// Reads the given response body into memory, stopping with `BodyTooLarge` if it goes over the
+/// limit set in the `ConnectionConfig`.
+async fn read_body(mut body: Body) -> Result<Vec<u8>> {
+    let max = max_body_bytes();
+    let mut buf = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if let Some(max) = max {
+            if buf.len() + chunk.len() > max {
+                return Err(BodyTooLarge(max));
+            }
+        }
+        buf.extend_from_slice(&chunk);
+    }
+
+    Ok(buf)
+}
+
 // n.b. this function is re-exported in the `raw` module - these docs are public!
 /// Loads the given request and discards the response body after parsing it for rate-limit and
 /// error information, returning the rate-limit information from the headers.
 pub async fn request_with_empty_response(request: Request<Body>) -> Result<Response<()>> {
     let (headers, _) = raw_request(request).await?;
-    let rate_limit_status = RateLimit::try_from(&headers)?;
-    Ok(Response {
-        rate_limit_status,
-        response: (),
-    })
+    Response::from_headers(&headers, ())
 }
 
 // n.b. this function is re-exported in the `raw` module - these docs are public!
@@ -237,11 +680,7 @@ pub async fn request_with_json_response<T: DeserializeOwned>(
 ) -> Result<Response<T>> {
     let (headers, body) = raw_request(request).await?;
     let response = serde_json::from_slice(&body)?;
-    let rate_limit_status = RateLimit::try_from(&headers)?;
-    Ok(Response {
-        rate_limit_status,
-        response,
-    })
+    Response::from_headers(&headers, response)
 }
 
 // n.b. this type is exported at the crate root - these docs are public!
@@ -262,7 +701,7 @@ pub async fn request_with_json_response<T: DeserializeOwned>(
 /// limits][rate-limit].
 ///
 /// [rate-limit]: https://developer.twitter.com/en/docs/basics/rate-limiting
-#[derive(Copy, Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 pub struct RateLimit {
     /// The rate limit ceiling for the given request.
     pub limit: i32,
@@ -272,6 +711,52 @@ pub struct RateLimit {
     pub reset: i32,
 }
 
+impl RateLimit {
+    /// Returns whether the response came with rate-limit information.
+    ///
+    /// Each field of a `RateLimit` is `-1` when its header was missing from the response, which
+    /// happens for calls that aren't rate-limited per window. This returns `true` only if all three
+    /// fields are known.
+    pub fn is_known(&self) -> bool {
+        self.limit >= 0 && self.remaining >= 0 && self.reset >= 0
+    }
+
+    /// Returns whether the rate limit has been used up, so that calling the same endpoint again
+    /// before `reset` would fail.
+    ///
+    /// This is `true` when `remaining` is zero and the reset time is still in the future. If the
+    /// rate-limit information is unknown (see `is_known`), this returns `false`, since there's
+    /// nothing to wait for.
+    pub fn should_wait(&self) -> bool {
+        match self.reset_time() {
+            Some(reset) => self.remaining == 0 && reset > chrono::Utc::now(),
+            None => false,
+        }
+    }
+
+    /// Returns the time at which the rate window resets, or `None` if the response didn't say.
+    ///
+    /// This is `reset` converted from a Unix timestamp. If the response didn't come with rate-limit
+    /// headers, `reset` is `-1`, and this returns `None` instead of a time in 1969.
+    pub fn reset_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        if self.reset < 0 {
+            return None;
+        }
+        chrono::Utc.timestamp_opt(i64::from(self.reset), 0).single()
+    }
+
+    /// Returns how long it is from the given time until the rate window resets, or `None` if the
+    /// response didn't say when it resets.
+    ///
+    /// If the window has already reset by `now`, this returns a zero duration. Passing in the
+    /// current time, as in `reset_in(chrono::Utc::now())`, gives how long to wait before the limit
+    /// refreshes.
+    pub fn reset_in(&self, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+        let wait = self.reset_time()? - now;
+        Some(wait.to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+    }
+}
+
 impl TryFrom<&Headers> for RateLimit {
     type Error = Error;
     fn try_from(headers: &Headers) -> Result<Self> {
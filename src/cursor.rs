@@ -239,6 +239,8 @@ where
     ///some calls don't allow you to set the size of the pages at all. Refer to the individual
     ///methods' documentation for specifics.
     pub page_size: Option<i32>,
+    ///The largest page size the endpoint accepts, if it's known.
+    max_page_size: Option<i32>,
     ///Numeric reference to the previous page of results. A value of zero indicates that the
     ///current page of results is the first page of the cursor.
     ///
@@ -253,6 +255,7 @@ where
     ///implementation. It is made available for those who wish to manually manage network calls and
     ///pagination.
     pub next_cursor: i64,
+    page_cursor: i64,
     loader: Option<FutureResponse<T>>,
     iter: Option<Box<dyn Iterator<Item = Response<T::Item>> + Send>>,
 }
@@ -265,16 +268,19 @@ where
     ///
     ///Certain calls set their own minimums and maximums for what this value can be. Furthermore,
     ///some calls don't allow you to set the size of the pages at all. Refer to the individual
-    ///methods' documentation for specifics. If this method is called for a response that does not
-    ///accept changing the page size, no change to the underlying struct will occur.
+    ///methods' documentation for specifics. The given size is kept between 1 and the largest page
+    ///size the call accepts. If this method is called for a response that does not accept changing
+    ///the page size, it does nothing and returns the struct unchanged.
     ///
     ///Calling this function will invalidate any current results, if any were previously loaded.
     pub fn with_page_size(self, page_size: i32) -> CursorIter<T> {
         if self.page_size.is_some() {
+            let page_size = page_size.clamp(1, self.max_page_size.unwrap_or(i32::MAX));
             CursorIter {
                 page_size: Some(page_size),
                 previous_cursor: -1,
                 next_cursor: -1,
+                page_cursor: -1,
                 loader: None,
                 iter: None,
                 ..self
@@ -284,23 +290,132 @@ where
         }
     }
 
+    ///Sets the cursor to start loading results from, such as one saved from `current_cursor` by
+    ///an earlier run.
+    ///
+    ///A cursor of `0` or `-1` starts from the first page. Since `0` is also what `next_cursor`
+    ///holds once every page has been loaded, a saved cursor from a finished run starts over
+    ///instead of loading nothing.
+    ///
+    ///Calling this function will invalidate any current results, if any were previously loaded.
+    pub fn resume_at(self, cursor: i64) -> CursorIter<T> {
+        let cursor = if cursor == 0 { -1 } else { cursor };
+        CursorIter {
+            previous_cursor: -1,
+            next_cursor: cursor,
+            page_cursor: cursor,
+            loader: None,
+            iter: None,
+            ..self
+        }
+    }
+
+    ///Returns the cursor of the page the `Stream` implementation is currently returning results
+    ///from, or `-1` before any page has been loaded.
+    ///
+    ///To save your place and pick it up later, store this value and give it to `resume_at`. The
+    ///page it points to will be loaded again, so results from it that were already returned will
+    ///be returned again, but none will be skipped. Saving `next_cursor` instead would skip
+    ///whatever is left of the current page.
+    pub fn current_cursor(&self) -> i64 {
+        self.page_cursor
+    }
+
     ///Loads the next page of results.
     ///
     ///This is intended to be used as part of this struct's Iterator implementation. It is provided
     ///as a convenience for those who wish to manage network calls and pagination manually.
     pub fn call(&self) -> impl Future<Output = Result<Response<T>>> {
+        self.call_at(self.next_cursor)
+    }
+
+    ///Loads the most recently loaded page of results again, and returns its fresh contents.
+    ///
+    ///This re-reads the page at the cursor position the last page was loaded from, which is useful
+    ///to poll a page for changes without starting over. If no page has been loaded yet, this loads
+    ///the first page. To go back to the first page regardless of position, use
+    ///`reset_and_refresh` instead.
+    ///
+    ///`previous_cursor` and `next_cursor` are updated from the fresh page. Any results that were
+    ///loaded but not yet returned by the `Stream` implementation are discarded, and the stream
+    ///picks up with the page after this one.
+    pub async fn refresh(&mut self) -> Result<Response<Vec<T::Item>>> {
+        self.loader = None;
+        self.iter = None;
+
+        let resp = self.call_at(self.page_cursor).await?;
+        self.previous_cursor = resp.previous_cursor_id();
+        self.next_cursor = resp.next_cursor_id();
+
+        Ok(Response::map(resp, |r| r.into_inner()))
+    }
+
+    ///Goes back to the first page of results, and loads it.
+    ///
+    ///This works like `refresh`, but always loads the first page, as if the `CursorIter` had just
+    ///been created.
+    pub async fn reset_and_refresh(&mut self) -> Result<Response<Vec<T::Item>>> {
+        self.page_cursor = -1;
+        self.refresh().await
+    }
+
+    ///Loads the page of results at the given cursor position.
+    fn call_at(&self, cursor: i64) -> impl Future<Output = Result<Response<T>>> {
         let params = self
             .params_base
             .as_ref()
             .cloned()
             .unwrap_or_default()
-            .add_param("cursor", self.next_cursor.to_string())
+            .add_param("cursor", cursor.to_string())
             .add_opt_param("count", self.page_size.map_string());
 
         let req = get(self.link, &self.token, Some(&params));
         request_with_json_response(req)
     }
 
+    ///Loads every remaining page of results into one `Vec`, making at most `max_calls` network
+    ///calls to do so.
+    ///
+    ///Along with the collected results, the future returned by this function yields whether the
+    ///collection was cut short by `max_calls`: `true` means there were more pages left to load when
+    ///the budget ran out, and `false` means every page was loaded. The rate-limit information of
+    ///the pages is combined the same way as collecting `Response`s with `FromIterator`, or is `-1`
+    ///in every field if nothing was loaded.
+    ///
+    ///Loading starts from `next_cursor`, so this picks up where any manual paging left off. Any
+    ///results the `Stream` implementation already loaded but hasn't returned yet come first, and
+    ///don't count against `max_calls`.
+    pub async fn collect_all_within(
+        mut self,
+        max_calls: usize,
+    ) -> Result<(Response<Vec<T::Item>>, bool)> {
+        let mut pages: Vec<Response<Vec<T::Item>>> = Vec::new();
+        self.loader = None;
+        if let Some(buffered) = self.iter.take() {
+            let buffered: Response<Vec<T::Item>> = buffered.collect();
+            if !buffered.response.is_empty() {
+                pages.push(buffered);
+            }
+        }
+
+        for _ in 0..max_calls {
+            if self.next_cursor == 0 {
+                break;
+            }
+
+            self.page_cursor = self.next_cursor;
+            let resp = self.call().await?;
+            self.previous_cursor = resp.previous_cursor_id();
+            self.next_cursor = resp.next_cursor_id();
+            pages.push(Response::map(resp, |r| r.into_inner()));
+        }
+
+        let truncated = self.next_cursor != 0;
+        let pages = pages.into_iter().collect::<Response<Vec<_>>>();
+        let items = Response::map(pages, |pages| pages.into_iter().flatten().collect());
+        Ok((items, truncated))
+    }
+
     ///Creates a new instance of CursorIter, with the given parameters and empty initial results.
     ///
     ///This is essentially an internal infrastructure function, not meant to be used from consumer
@@ -310,20 +425,55 @@ where
         token: &auth::Token,
         params_base: Option<ParamList>,
         page_size: Option<i32>,
+        max_page_size: Option<i32>,
     ) -> CursorIter<T> {
         CursorIter {
             link,
             token: token.clone(),
             params_base,
             page_size,
+            max_page_size,
             previous_cursor: -1,
             next_cursor: -1,
+            page_cursor: -1,
             loader: None,
             iter: None,
         }
     }
 }
 
+impl<T> CursorIter<T>
+where
+    T: Cursor + DeserializeOwned + 'static,
+    T::Item: Unpin + Send,
+{
+    ///Loads every remaining result into one `Vec`, stopping early once `limit` results have been
+    ///loaded, if a limit is given.
+    ///
+    ///This drives the `Stream` implementation to the end of the cursor, so it picks up wherever
+    ///the stream left off. The rate-limit information of the pages is combined the same way as
+    ///collecting `Response`s with `FromIterator`, keeping the one from the latest rate-limit
+    ///window.
+    ///
+    ///If any page fails to load, including because the rate limit ran out, the error is returned
+    ///right away and the results loaded so far are dropped. This doesn't wait for the rate limit
+    ///to reset; to do that, wrap the call in `auto_retry`, or use `collect_all_within` to cap the
+    ///number of network calls instead.
+    ///
+    ///Be careful calling this without a limit on a large collection. For example, an account with
+    ///millions of followers takes thousands of calls to load with `user::followers_ids`, far more
+    ///than one rate-limit window allows, and holds every result in memory at once.
+    pub async fn call_all(self, limit: Option<usize>) -> Result<Response<Vec<T::Item>>> {
+        use futures::{StreamExt, TryStreamExt};
+
+        let pages = self
+            .take(limit.unwrap_or(usize::MAX))
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(pages.into_iter().collect())
+    }
+}
+
 impl<T> Stream for CursorIter<T>
 where
     T: Cursor + DeserializeOwned + 'static,
@@ -344,17 +494,22 @@ where
 
                     let resp = Response::map(resp, |r| r.into_inner());
                     let rate = resp.rate_limit_status;
+                    let headers = resp.extra_headers;
 
                     let mut iter = Box::new(resp.response.into_iter().map(move |item| Response {
                         rate_limit_status: rate,
                         response: item,
+                        extra_headers: headers.clone(),
                     }));
                     let first = iter.next();
                     self.iter = Some(iter);
 
                     match first {
                         Some(item) => return Poll::Ready(Some(Ok(item))),
-                        None => return Poll::Ready(None),
+                        //Twitter can send an empty page in the middle of a cursor, so only stop
+                        //once the cursor says there's nothing left
+                        None if self.next_cursor == 0 => return Poll::Ready(None),
+                        None => (),
                     }
                 }
                 Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
@@ -369,7 +524,204 @@ where
             }
         }
 
+        self.page_cursor = self.next_cursor;
         self.loader = Some(Box::pin(self.call()));
         self.poll_next(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::IDCursor;
+    use crate::auth::Token;
+    use crate::common::tests::serve_sequence;
+    use crate::user;
+
+    use futures::TryStreamExt;
+
+    fn page(ids: &[u64], next_cursor: i64, remaining: i32) -> Vec<u8> {
+        let body = serde_json::json!({
+            "ids": ids,
+            "previous_cursor": 0,
+            "next_cursor": next_cursor,
+        })
+        .to_string();
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nx-rate-limit-limit: 15\r\nx-rate-limit-remaining: {}\r\nx-rate-limit-reset: 1600000000\r\nContent-Length: {}\r\n\r\n{}",
+            remaining,
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn call_all_pages() {
+        let responses = vec![
+            page(&[1, 2], 10, 14),
+            page(&[3, 4], 20, 13),
+            page(&[5], 0, 12),
+        ];
+        let (addr, requests) = serve_sequence(responses.clone());
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let token = Token::Bearer("abc".to_string());
+
+        let all = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None)
+            .call_all(None)
+            .await
+            .unwrap();
+        assert_eq!(all.response, [1, 2, 3, 4, 5]);
+        assert_eq!(all.rate_limit_status.remaining, 12);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // a limit stops loading pages once it's reached
+        let (addr, requests) = serve_sequence(responses);
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let some = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None)
+            .call_all(Some(3))
+            .await
+            .unwrap();
+        assert_eq!(some.response, [1, 2, 3]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn call_all_rate_limit() {
+        let body = r#"{"errors":[{"code":88,"message":"Rate limit exceeded"}]}"#;
+        let limited = format!(
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Type: application/json\r\nx-rate-limit-limit: 15\r\nx-rate-limit-remaining: 0\r\nx-rate-limit-reset: 1600000000\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (addr, requests) = serve_sequence(vec![page(&[1, 2], 10, 1), limited.into_bytes()]);
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let token = Token::Bearer("abc".to_string());
+
+        let err = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None)
+            .call_all(None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::error::Error::RateLimit(1600000000)));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resume_checkpoint() {
+        use futures::StreamExt;
+
+        let responses = vec![page(&[1, 2, 3], 10, 14), page(&[4, 5, 6], 0, 13)];
+        let (addr, requests) = serve_sequence(responses.clone());
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let token = Token::Bearer("abc".to_string());
+
+        let mut iter = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None);
+        assert_eq!(iter.current_cursor(), -1);
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(iter.next().await.unwrap().unwrap().response);
+        }
+        assert_eq!(seen, [1, 2, 3, 4]);
+        let checkpoint = iter.current_cursor();
+        assert_eq!(checkpoint, 10);
+        drop(iter);
+
+        // "restart" from the saved cursor: the second page is loaded again
+        let (addr, resumed_requests) = serve_sequence(vec![responses[1].clone()]);
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let rest = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None)
+            .resume_at(checkpoint)
+            .call_all(None)
+            .await
+            .unwrap();
+        assert_eq!(rest.response, [4, 5, 6]);
+
+        assert!(requests.lock().unwrap()[1].contains("cursor=10"));
+        let resumed_requests = resumed_requests.lock().unwrap();
+        assert_eq!(resumed_requests.len(), 1);
+        assert!(resumed_requests[0].contains("cursor=10"));
+
+        // a finished cursor starts over
+        let restarted =
+            crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None).resume_at(0);
+        assert_eq!(restarted.next_cursor, -1);
+        assert_eq!(restarted.current_cursor(), -1);
+    }
+
+    #[tokio::test]
+    async fn collect_all_within_buffered() {
+        use futures::StreamExt;
+
+        let (addr, requests) = serve_sequence(vec![
+            page(&[1, 2, 3], 10, 14),
+            page(&[4, 5], 20, 13),
+            page(&[6], 0, 12),
+        ]);
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let token = Token::Bearer("abc".to_string());
+
+        let mut iter = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None);
+        assert_eq!(iter.next().await.unwrap().unwrap().response, 1);
+
+        // the rest of the first page is kept, and the budget only covers new calls
+        let (rest, truncated) = iter.collect_all_within(1).await.unwrap();
+        assert_eq!(rest.response, [2, 3, 4, 5]);
+        assert!(truncated);
+        assert_eq!(rest.rate_limit_status.remaining, 13);
+        assert_eq!(rest.rate_limit_status.limit, 15);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].contains("cursor=10"));
+    }
+
+    #[tokio::test]
+    async fn stream_items() {
+        let (addr, requests) = serve_sequence(vec![
+            page(&[1, 2, 3], 10, 14),
+            page(&[], 20, 13),
+            page(&[4, 5], 0, 12),
+        ]);
+        let link: &'static str = Box::leak(format!("http://{}/ids.json", addr).into());
+        let token = Token::Bearer("abc".to_string());
+
+        let items = crate::raw::request_as_cursor_iter::<IDCursor>(link, &token, None, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        let ids = items.iter().map(|r| r.response).collect::<Vec<_>>();
+        assert_eq!(ids, [1, 2, 3, 4, 5]);
+        let remaining = items
+            .iter()
+            .map(|r| r.rate_limit_status.remaining)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, [14, 14, 14, 12, 12]);
+        assert!(items.iter().all(|r| r.rate_limit_status.limit == 15));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].contains("cursor=-1"));
+        assert!(requests[1].contains("cursor=10"));
+        assert!(requests[2].contains("cursor=20"));
+    }
+
+    #[test]
+    fn page_size_limits() {
+        let token = Token::Bearer("abc".to_string());
+
+        let ids = user::friends_ids("rustlang", &token).with_page_size(5000);
+        assert_eq!(ids.page_size, Some(5000));
+        let ids = user::friends_ids("rustlang", &token).with_page_size(6000);
+        assert_eq!(ids.page_size, Some(5000));
+        let ids = user::friends_ids("rustlang", &token).with_page_size(0);
+        assert_eq!(ids.page_size, Some(1));
+
+        let users = user::friends_of("rustlang", &token).with_page_size(5000);
+        assert_eq!(users.page_size, Some(200));
+
+        let blocks = user::blocks(&token).with_page_size(5000);
+        assert_eq!(blocks.page_size, None);
+        let blocks = user::blocks_ids(&token).with_page_size(5000);
+        assert_eq!(blocks.page_size, None);
+    }
+}
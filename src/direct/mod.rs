@@ -598,3 +598,59 @@ impl DraftMessage {
         Ok(Response::into(resp))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{raw, DirectMessage};
+    use crate::common::tests::load_file;
+
+    #[test]
+    fn parse_single() {
+        let sample = load_file("sample_payloads/direct_message.json");
+        let event: raw::SingleEvent = serde_json::from_str(&sample).unwrap();
+        let dm = DirectMessage::from(event);
+
+        assert_eq!(dm.id, 1110302988680650757);
+        assert_eq!(dm.sender_id, 3805104374);
+        assert_eq!(dm.recipient_id, 1113935270);
+        assert_eq!(dm.created_at.timestamp_millis(), 1553616478193);
+        assert_eq!(dm.text, "Café time? #rustlang https://t.co/abc123");
+        assert_eq!(dm.source_app.unwrap().name, "Twitter Web Client");
+
+        // entity indices come in as codepoints and should be translated to bytes
+        let hashtag = &dm.entities.hashtags[0];
+        assert_eq!(&dm.text[hashtag.range.0..hashtag.range.1], "#rustlang");
+        let url = &dm.entities.urls[0];
+        assert_eq!(&dm.text[url.range.0..url.range.1], "https://t.co/abc123");
+        assert_eq!(
+            url.expanded_url.as_deref(),
+            Some("https://www.rust-lang.org")
+        );
+
+        assert!(dm.attachment.is_none());
+        assert!(dm.ctas.is_none());
+        assert!(dm.quick_replies.is_none());
+    }
+
+    #[test]
+    fn parse_cursor() {
+        let sample = load_file("sample_payloads/direct_message_list.json");
+        let page: raw::EventCursor = serde_json::from_str(&sample).unwrap();
+        assert_eq!(
+            page.next_cursor.as_deref(),
+            Some("MTExMDMwMTg3MDYyNjYxOTM5OQ")
+        );
+
+        let dms = Vec::<DirectMessage>::from(page);
+        assert_eq!(dms.len(), 2);
+
+        // only messages sent by the authenticated user carry source-app information
+        assert!(dms[0].source_app.is_some());
+        assert!(dms[1].source_app.is_none());
+
+        let mention = &dms[1].entities.user_mentions[0];
+        assert_eq!(mention.screen_name, "QuoteBot");
+        assert_eq!(&dms[1].text[mention.range.0..mention.range.1], "@QuoteBot");
+        assert_eq!(dms[1].sender_id, dms[0].recipient_id);
+    }
+}
@@ -42,10 +42,25 @@
 //! - `display_url`: This is a truncated version of `expanded_url`, meant to be displayed inline
 //!   with the parent text. This is useful to show users where the link resolves to, without
 //!   potentially filling up a lot of space with the fullly expanded URL.
+//!
+//! If you need to know where a link actually ends up, for example when `expanded_url` is itself a
+//! link from another shortener, `resolve_tco` can follow the redirects from a shortened URL to its
+//! final destination.
+use std::time::Duration;
+
+use hyper::{header, Body, Method, Request};
 use mime;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::common::serde_via_string;
+use crate::common::{request_timeout, send, serde_via_string};
+use crate::error::{self, Error};
+
+/// The most redirects `resolve_tco` will follow before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+/// How long `resolve_tco` will wait for all its requests to finish, if `ConnectionConfig::timeout`
+/// hasn't been set.
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(30);
 
 ///Represents a hashtag or symbol extracted from another piece of text.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -235,3 +250,111 @@ where
     let opt = Option::deserialize(deserializer)?;
     Ok(opt.unwrap_or_default())
 }
+
+///Follows the redirects from the given shortened URL, like a `t.co` link, and returns the URL it
+///finally leads to.
+///
+///This sends `HEAD` requests through egg-mode's HTTP client, so the body of each page is never
+///downloaded. It follows at most 10 redirects, and gives up once all its requests together take
+///longer than the timeout set with `ConnectionConfig::timeout`, or 30 seconds if none was set. If
+///the chain of redirects is longer than that, it returns `Error::InvalidResponse` with the last URL
+///it reached; if it runs out of time, it returns `Error::Timeout`.
+///
+///The final URL is the first one that doesn't respond with a redirect, regardless of its status
+///code, so this doesn't check whether the final page actually loads.
+///
+///Note that this doesn't call Twitter's API, so it doesn't count against any rate limits, and
+///doesn't need a `Token`.
+pub async fn resolve_tco(url: &str) -> error::Result<String> {
+    let limit = request_timeout().unwrap_or(RESOLVE_TIMEOUT);
+    match tokio::time::timeout(limit, follow_redirects(url)).await {
+        Ok(res) => res,
+        Err(_) => Err(Error::Timeout(limit)),
+    }
+}
+
+async fn follow_redirects(url: &str) -> error::Result<String> {
+    let mut current = url::Url::parse(url)
+        .map_err(|_| Error::InvalidResponse("invalid URL", Some(url.into())))?;
+
+    for _ in 0..MAX_REDIRECTS {
+        let request = Request::builder()
+            .method(Method::HEAD)
+            .uri(current.as_str())
+            .body(Body::empty())
+            .map_err(|_| Error::InvalidResponse("invalid URL", Some(current.to_string())))?;
+        let resp = send(request).await?;
+
+        if !resp.status().is_redirection() {
+            return Ok(current.into());
+        }
+
+        let location = match resp.headers().get(header::LOCATION) {
+            Some(location) => location.to_str()?,
+            None => return Ok(current.into()),
+        };
+        // `Location` is allowed to be relative to the URL that sent it
+        current = current.join(location).map_err(|_| {
+            Error::InvalidResponse("invalid redirect location", Some(location.into()))
+        })?;
+    }
+
+    Err(Error::InvalidResponse(
+        "too many redirects",
+        Some(current.into()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_tco;
+    use crate::error::Error;
+    use crate::raw::{with_transport, Headers, HttpTransport, MockTransport, TransportFuture};
+
+    use hyper::{header, Body, Method, Request, StatusCode};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn resolve_redirects() {
+        let redirect = |location: &'static str| {
+            let mut headers = Headers::new();
+            headers.insert(header::LOCATION, location.parse().unwrap());
+            headers
+        };
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::MOVED_PERMANENTLY,
+                redirect("https://bit.ly/abc"),
+                "",
+            )
+            .respond(StatusCode::FOUND, redirect("/final?x=1"), "")
+            .respond(StatusCode::OK, Headers::new(), "");
+
+        let resolved = with_transport(mock.clone(), resolve_tco("https://t.co/abc"))
+            .await
+            .unwrap();
+        assert_eq!(resolved, "https://bit.ly/final?x=1");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.method == Method::HEAD));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resolve_timeout() {
+        #[derive(Debug)]
+        struct Hang;
+
+        impl HttpTransport for Hang {
+            fn send(&self, _: Request<Body>) -> TransportFuture {
+                Box::pin(futures::future::pending())
+            }
+        }
+
+        let err = with_transport(Hang, resolve_tco("https://t.co/abc"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout(limit) if limit == Duration::from_secs(30)));
+        assert!(err.is_transient());
+    }
+}
@@ -119,19 +119,91 @@ pub enum Error {
     ///rate-limit window will open.
     #[error("Rate limit reached, hold until {}", _0)]
     RateLimit(i32),
+    ///Twitter rejected the credentials used to sign the request, either because they were
+    ///revoked, expired, or otherwise invalid, or because the OAuth signature didn't match.
+    ///
+    ///This is returned for a response with one of the Twitter error codes 32 ("Could not
+    ///authenticate you"), 89 ("Invalid or expired token"), 99 ("Unable to verify your
+    ///credentials"), or 135 ("Timestamp out of bounds"), or for a `401 Unauthorized` response
+    ///that didn't carry an error code. This usually means the user needs to sign in again, or a
+    ///new Bearer token needs to be requested.
+    ///
+    ///The enclosed values are the error code and message Twitter returned. If the response didn't
+    ///have an error code, `code` is `-1` and `message` is the HTTP status text.
+    #[error("Authentication failed: #{code}: {message}")]
+    Unauthorized {
+        ///The numeric error code returned by Twitter, or `-1` if there wasn't one.
+        code: i32,
+        ///The error message returned by Twitter, or the HTTP status text if there wasn't one.
+        message: String,
+    },
     ///An attempt to upload a video or gif successfully uploaded the file, but failed in
     ///post-processing. The enclosed value contains the error message from Twitter.
     #[error("Error processing media: {}", _0)]
     MediaError(#[from] MediaError),
-    ///The response from Twitter gave a response code that indicated an error. The enclosed value
-    ///was the response code.
+    ///The response from Twitter gave a response code that indicated an error. The enclosed values
+    ///are the response code and the body of the response, which often explains what went wrong.
     ///
     ///This is only returned if Twitter did not also return an [error code][TwitterErrors] in the
-    ///response body. That check is performed before examining the status code.
+    ///response body. That check is performed before examining the status code. A `401
+    ///Unauthorized` status is returned as `Unauthorized` instead.
+    ///
+    ///The body is cut off after its first 4 KiB, so that logging this error doesn't print an entire
+    ///HTML page. Any bytes that aren't valid UTF-8 are replaced with `U+FFFD`. For the streaming
+    ///API, the body isn't read, so it's always empty.
     ///
     ///[TwitterErrors]: struct.TwitterErrors.html
-    #[error("Error status received: {}", _0)]
-    BadStatus(hyper::StatusCode),
+    #[error("Error status received: {} ({:?})", _0, _1)]
+    BadStatus(hyper::StatusCode, String),
+    ///Twitter refused to post a tweet because the authenticated user already posted one with the
+    ///same text (error code 187, "Status is a duplicate").
+    ///
+    ///When this comes from `DraftTweet::send`, egg-mode looks through the user's most recent
+    ///tweets for the one with the same text, and the enclosed value is its ID if it was found.
+    ///Otherwise, or when it comes from some other call, the enclosed value is `None`.
+    #[error("Status is a duplicate of {:?}", _0)]
+    DuplicateStatus(Option<u64>),
+    ///The account a call was made about doesn't exist, or was deleted (error code 50, "User not
+    ///found"). The enclosed value is the error message Twitter returned.
+    #[error("User not found: {}", _0)]
+    UserNotFound(String),
+    ///The account a call was made about has been suspended (error code 63, "User has been
+    ///suspended"). The enclosed value is the error message Twitter returned.
+    #[error("User has been suspended: {}", _0)]
+    UserSuspended(String),
+    ///A tweet given to `tweet::post_thread` was longer than Twitter allows, so nothing was
+    ///posted. The enclosed values are the position of the tweet in the thread, starting from
+    ///zero, and its length in characters.
+    #[error("Tweet #{index} in thread is too long: {length} characters")]
+    TweetTooLong {
+        ///The position of the tweet in the thread, starting from zero.
+        index: usize,
+        ///The length of the tweet, in characters.
+        length: usize,
+    },
+    ///A coordinate given to `SearchBuilder::geocode` was out of range, so the search wasn't sent.
+    ///Latitudes must be between -90 and 90, and longitudes between -180 and 180. The enclosed
+    ///values are the latitude and longitude that were given.
+    #[error("Invalid coordinate: ({}, {})", _0, _1)]
+    InvalidCoordinate(f32, f32),
+    ///`user::cancel_follow_request` was called, but Twitter still reported a pending follow request
+    ///to the account afterward. The enclosed value is the ID of the account.
+    #[error("Follow request to user {} was not cancelled", _0)]
+    FollowRequestNotCancelled(u64),
+    ///The description given to `account::update_profile` was longer than the 160 characters
+    ///Twitter allows, so the profile wasn't updated. The enclosed value is the length of the
+    ///description, in characters.
+    #[error("Profile description is too long: {} characters", _0)]
+    DescriptionTooLong(usize),
+    ///The response from Twitter was larger than the limit set with
+    ///`ConnectionConfig::max_body_bytes`, so it was abandoned. The enclosed value is the limit, in
+    ///bytes.
+    #[error("Response body exceeded the limit of {} bytes", _0)]
+    BodyTooLarge(usize),
+    ///The response from Twitter took longer than the limit set with `ConnectionConfig::timeout`,
+    ///so it was abandoned. The enclosed value is the limit.
+    #[error("Request timed out after {:?}", _0)]
+    Timeout(std::time::Duration),
     ///The web request experienced an error. The enclosed error was returned from hyper.
     #[error("Network error: {}", _0)]
     NetError(#[from] hyper::Error),
@@ -171,3 +243,199 @@ pub enum Error {
     #[error("Error converting headers: {}", _0)]
     HeaderConvertError(#[from] std::num::ParseIntError),
 }
+
+/// Twitter error codes for problems on Twitter's end that usually clear up on their own: 130 ("Over
+/// capacity") and 131 ("Internal error").
+const TRANSIENT_ERROR_CODES: &[i32] = &[130, 131];
+
+impl Error {
+    /// Returns whether making the same call again later could succeed.
+    ///
+    /// This sorts errors by whether they came from the state of Twitter or the network at the time
+    /// of the call, or from the call itself:
+    ///
+    /// * `RateLimit` is retryable, once the rate window resets. `retry_after` says when that is.
+    /// * A `BadStatus` with a `5xx` status is retryable, since it means something went wrong on
+    ///   Twitter's end. So is a `TwitterError` for Twitter being over capacity or having an
+    ///   internal error (codes 130 and 131).
+    /// * `NetError` is retryable, since a dropped or refused connection may work on the next try.
+    ///   The exception is a `NetError` for a response hyper couldn't parse, or for a request it
+    ///   couldn't send as given, which will fail the same way again. An `IOError` is only
+    ///   retryable if it's about the connection, like one that was reset, refused, or cut off
+    ///   early; one for data that couldn't be read, like a corrupt compressed body, isn't. A
+    ///   `Timeout` is retryable for the same reason as a dropped connection.
+    /// * Everything else isn't retryable: a `4xx` status or other `TwitterError` means Twitter
+    ///   rejected the call itself, `Unauthorized` needs new credentials, and errors from parsing a
+    ///   response or checking the inputs to a call will happen again with the same inputs.
+    ///
+    /// This doesn't say how long to wait before trying again. `auto_retry` and `retry_transient`
+    /// handle the waiting for the most common cases.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimit(_) => true,
+            Error::BadStatus(status, _) => status.is_server_error(),
+            Error::TwitterError(_, errors) => errors.has_transient(),
+            Error::NetError(e) => !e.is_parse() && !e.is_user(),
+            Error::IOError(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::UnexpectedEof
+                    | std::io::ErrorKind::Interrupted
+            ),
+            Error::Timeout(_) => true,
+            _ => false,
+        }
+    }
+
+    /// If this is a `RateLimit` error, returns the Unix timestamp in UTC when the rate window
+    /// resets, after which the call can be made again.
+    pub fn retry_after(&self) -> Option<i32> {
+        match self {
+            Error::RateLimit(reset) => Some(*reset),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this error is likely to go away if the call is made again right away, as
+    /// used by `retry_transient`.
+    ///
+    /// This is narrower than `is_retryable`: it leaves out `RateLimit`, which needs to wait for the
+    /// rate window to reset, and only counts a `502`, `503`, or `504` status, Twitter error 130 or
+    /// 131, a `Timeout`, or a network error for a connection that couldn't be made or was cut off.
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            Error::BadStatus(status, _) => matches!(
+                *status,
+                hyper::StatusCode::BAD_GATEWAY
+                    | hyper::StatusCode::SERVICE_UNAVAILABLE
+                    | hyper::StatusCode::GATEWAY_TIMEOUT
+            ),
+            Error::TwitterError(_, errors) => errors.has_transient(),
+            Error::Timeout(_) => true,
+            Error::NetError(e) => {
+                if e.is_connect() || e.is_incomplete_message() {
+                    return true;
+                }
+                let mut source = std::error::Error::source(e);
+                while let Some(inner) = source {
+                    if let Some(io) = inner.downcast_ref::<std::io::Error>() {
+                        return matches!(
+                            io.kind(),
+                            std::io::ErrorKind::ConnectionReset
+                                | std::io::ErrorKind::ConnectionAborted
+                        );
+                    }
+                    source = inner.source();
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TwitterErrors {
+    /// Returns whether any of these errors is one that usually clears up on its own.
+    fn has_transient(&self) -> bool {
+        self.errors
+            .iter()
+            .any(|e| TRANSIENT_ERROR_CODES.contains(&e.code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn twitter_error(code: i32) -> Error {
+        Error::TwitterError(
+            Headers::new(),
+            TwitterErrors {
+                errors: vec![TwitterErrorCode {
+                    message: "error".to_string(),
+                    code,
+                }],
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn retry_classification() {
+        use hyper::StatusCode;
+
+        let rate_limit = Error::RateLimit(1600000000);
+        assert!(rate_limit.is_retryable());
+        assert_eq!(rate_limit.retry_after(), Some(1600000000));
+        assert!(!rate_limit.is_transient());
+
+        for status in &[
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            let err = Error::BadStatus(*status, String::new());
+            assert!(err.is_retryable(), "{} should be retryable", status);
+            assert_eq!(err.retry_after(), None);
+        }
+        assert!(!Error::BadStatus(StatusCode::INTERNAL_SERVER_ERROR, String::new()).is_transient());
+        assert!(Error::BadStatus(StatusCode::SERVICE_UNAVAILABLE, String::new()).is_transient());
+        for status in &[
+            StatusCode::BAD_REQUEST,
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+        ] {
+            let err = Error::BadStatus(*status, String::new());
+            assert!(!err.is_retryable(), "{} shouldn't be retryable", status);
+        }
+
+        assert!(twitter_error(130).is_retryable());
+        assert!(twitter_error(131).is_transient());
+        assert!(!twitter_error(34).is_retryable());
+        assert!(!twitter_error(34).is_transient());
+
+        let io = Error::IOError(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "reset",
+        ));
+        assert!(io.is_retryable());
+        // a corrupt body will be just as corrupt the next time
+        let invalid = Error::IOError(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "corrupt deflate stream",
+        ));
+        assert!(!invalid.is_retryable());
+        assert!(!invalid.is_transient());
+
+        // nothing listens on port 1, so this fails to connect
+        let refused = hyper::Client::new()
+            .get("http://127.0.0.1:1/".parse().unwrap())
+            .await
+            .unwrap_err();
+        let refused = Error::NetError(refused);
+        assert!(refused.is_retryable());
+        assert!(refused.is_transient());
+
+        let not_retryable = vec![
+            Error::Unauthorized {
+                code: 89,
+                message: "Invalid or expired token.".to_string(),
+            },
+            Error::DeserializeError(serde_json::from_str::<u64>("nope").unwrap_err()),
+            Error::MissingValue("id"),
+            Error::InvalidResponse("bad", None),
+            Error::DuplicateStatus(None),
+            Error::UserNotFound("User not found.".to_string()),
+            Error::BodyTooLarge(10),
+        ];
+        for err in not_retryable {
+            assert!(!err.is_retryable(), "{:?} shouldn't be retryable", err);
+            assert!(!err.is_transient(), "{:?} shouldn't be transient", err);
+            assert_eq!(err.retry_after(), None);
+        }
+    }
+}
@@ -64,8 +64,8 @@
 //!
 //! # Crate features
 //!
-//! While all of egg-mode's features are available by default, it allows you to configure how it
-//! connects to Twitter and how it uses HTTPS. The crate's Cargo features are the following:
+//! While nearly all of egg-mode's features are available by default, it allows you to configure
+//! how it connects to Twitter and how it uses HTTPS. The crate's Cargo features are the following:
 //!
 //! * `native_tls`: On by default. With this feature on, egg-mode uses `native-tls` to access your
 //!   operating system's native TLS functionality to access Twitter.
@@ -81,6 +81,20 @@
 //! compile error will result. If you need to use `rustls` or `rustls_webpki`, remember to set
 //! `default-features = false` in your Cargo.toml.
 //!
+//! Separately from the TLS features, there are a few more features you can turn on:
+//!
+//! * `gzip`: Off by default. With this feature on, egg-mode asks Twitter to compress its responses
+//!   with gzip or deflate, and decompresses them with `flate2` when they arrive. This can save a
+//!   lot of bandwidth when loading large timelines. Streams from the streaming API are still sent
+//!   uncompressed.
+//! * `blocking`: Off by default. With this feature on, the `blocking` module is available, with
+//!   versions of egg-mode's functions that wait for their result instead of being `async`. This is
+//!   handy for scripts and command-line tools that don't otherwise need an async runtime.
+//! * `debug_signatures`: Off by default. With this feature on, when Twitter rejects a request's
+//!   OAuth signature (error code 32), egg-mode prints the signature base string it signed to
+//!   stderr, to help track down encoding mismatches. The base string holds the consumer key, the
+//!   access token, and every parameter of the request, so only turn this on while debugging.
+//!
 //! # Types and Functions
 //!
 //! All of the main content of egg-mode is in submodules, but there are a few things here in the
@@ -126,6 +140,8 @@
 //!   their profile information, blocking or muting them, or showing the relationship between two
 //!   users.
 //! * `search`: Due to the complexity of searching for tweets, it gets its own module.
+//! * `saved_search`: This module lets you keep searches on a user's account, to list and run them
+//!   again later.
 //! * `direct`: Here you can work with a user's Direct Messages, either by loading DMs they've sent
 //!   or received, or by sending new ones.
 //! * `list`: This module lets you act on lists, from creating and deleting them, adding and
@@ -165,6 +181,8 @@
 mod common;
 pub mod account;
 pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod cursor;
 pub mod direct;
 pub mod entities;
@@ -174,6 +192,7 @@ pub mod list;
 pub mod media;
 pub mod place;
 pub mod raw;
+pub mod saved_search;
 pub mod search;
 pub mod service;
 pub mod stream;
@@ -182,4 +201,7 @@ pub mod tweet;
 pub mod user;
 
 pub use crate::auth::{KeyPair, Token};
-pub use crate::common::{RateLimit, Response, ResponseIter};
+pub use crate::common::{
+    auto_retry, resolve_tco, retry_transient, Backoff, MergePolicy, RateLimit, Response,
+    ResponseIter,
+};
@@ -59,6 +59,7 @@ pub mod statuses {
     pub const UNLIKE: &str = "https://api.twitter.com/1.1/favorites/destroy.json";
     pub const UPDATE: &str = "https://api.twitter.com/1.1/statuses/update.json";
     pub const DELETE_STEM: &str = "https://api.twitter.com/1.1/statuses/destroy";
+    pub const OEMBED: &str = "https://publish.twitter.com/oembed";
 }
 
 pub mod media {
@@ -94,6 +95,14 @@ pub mod account {
     pub const UPDATE_PROFILE_BNNER: &str =
         "https://api.twitter.com/1.1/account/update_profile_banner.json";
     pub const UPDATE_PROFILE: &str = "https://api.twitter.com/1.1/account/update_profile.json";
+    pub const SETTINGS: &str = "https://api.twitter.com/1.1/account/settings.json";
+}
+
+pub mod saved_searches {
+    pub const LIST: &str = "https://api.twitter.com/1.1/saved_searches/list.json";
+    pub const SHOW_STEM: &str = "https://api.twitter.com/1.1/saved_searches/show";
+    pub const CREATE: &str = "https://api.twitter.com/1.1/saved_searches/create.json";
+    pub const DESTROY_STEM: &str = "https://api.twitter.com/1.1/saved_searches/destroy";
 }
 
 pub mod place {
@@ -128,4 +137,5 @@ pub mod stream {
 pub mod trend {
     pub const CLOSEST: &str = "https://api.twitter.com/1.1/trends/closest.json";
     pub const AVAILABLE: &str = "https://api.twitter.com/1.1/trends/available.json";
+    pub const PLACE: &str = "https://api.twitter.com/1.1/trends/place.json";
 }
@@ -2,11 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::convert::TryFrom;
-
 use super::*;
 
-use crate::common::RateLimit;
 use crate::cursor::{CursorIter, ListCursor, UserCursor};
 use crate::error::{Error::TwitterError, Result};
 use crate::user::{TwitterUser, UserID};
@@ -18,7 +15,13 @@ use crate::{auth, links, tweet};
 ///reeturning 20 lists in a single network call; the maximum is 1000.
 pub fn memberships<T: Into<UserID>>(user: T, token: &auth::Token) -> CursorIter<ListCursor> {
     let params = ParamList::new().add_user_param(user.into());
-    CursorIter::new(links::lists::MEMBERSHIPS, token, Some(params), Some(20))
+    CursorIter::new(
+        links::lists::MEMBERSHIPS,
+        token,
+        Some(params),
+        Some(20),
+        Some(1000),
+    )
 }
 
 ///Return up to 100 lists the given user is subscribed to, including those the user made
@@ -32,7 +35,10 @@ pub fn memberships<T: Into<UserID>>(user: T, token: &auth::Token) -> CursorIter<
 ///
 ///If the user has more than 100 lists total like this, you'll need to call `ownerships` and
 ///`subscriptions` separately to be able to properly load everything.
-pub async fn list<'id, T: Into<UserID>>(
+///
+///Note that this does not include lists the given user has been added to by someone else, unless
+///they've also subscribed to them. To load those, use `memberships`.
+pub async fn list<T: Into<UserID>>(
     user: T,
     owned_first: bool,
     token: &auth::Token,
@@ -52,7 +58,13 @@ pub async fn list<'id, T: Into<UserID>>(
 ///reeturning 20 lists in a single network call; the maximum is 1000.
 pub fn subscriptions<T: Into<UserID>>(user: T, token: &auth::Token) -> CursorIter<ListCursor> {
     let params = ParamList::new().add_user_param(user.into());
-    CursorIter::new(links::lists::SUBSCRIPTIONS, token, Some(params), Some(20))
+    CursorIter::new(
+        links::lists::SUBSCRIPTIONS,
+        token,
+        Some(params),
+        Some(20),
+        Some(1000),
+    )
 }
 
 ///Look up the lists created by the given user.
@@ -61,7 +73,13 @@ pub fn subscriptions<T: Into<UserID>>(user: T, token: &auth::Token) -> CursorIte
 ///reeturning 20 lists in a single network call; the maximum is 1000.
 pub fn ownerships<T: Into<UserID>>(user: T, token: &auth::Token) -> CursorIter<ListCursor> {
     let params = ParamList::new().add_user_param(user.into());
-    CursorIter::new(links::lists::OWNERSHIPS, token, Some(params), Some(20))
+    CursorIter::new(
+        links::lists::OWNERSHIPS,
+        token,
+        Some(params),
+        Some(20),
+        Some(1000),
+    )
 }
 
 ///Look up information for a single list.
@@ -80,7 +98,13 @@ pub async fn show(list: ListID, token: &auth::Token) -> Result<Response<List>> {
 pub fn members(list: ListID, token: &auth::Token) -> CursorIter<UserCursor> {
     let params = ParamList::new().add_list_param(list);
 
-    CursorIter::new(links::lists::MEMBERS, token, Some(params), Some(20))
+    CursorIter::new(
+        links::lists::MEMBERS,
+        token,
+        Some(params),
+        Some(20),
+        Some(5000),
+    )
 }
 
 ///Look up the users that have subscribed to the given list.
@@ -90,7 +114,13 @@ pub fn members(list: ListID, token: &auth::Token) -> CursorIter<UserCursor> {
 pub fn subscribers(list: ListID, token: &auth::Token) -> CursorIter<UserCursor> {
     let params = ParamList::new().add_list_param(list);
 
-    CursorIter::new(links::lists::SUBSCRIBERS, token, Some(params), Some(20))
+    CursorIter::new(
+        links::lists::SUBSCRIBERS,
+        token,
+        Some(params),
+        Some(20),
+        Some(5000),
+    )
 }
 
 ///Check whether the given user is subscribed to the given list.
@@ -115,7 +145,7 @@ pub async fn is_subscribed<'id, T: Into<UserID>>(
                 // the rate limit info that would otherwise be part of the response isn't there. the
                 // rate_headers method was factored out specifically for this location, since it's
                 // still there, just accompanying an error response instead of a user.
-                Ok(Response::new(RateLimit::try_from(&headers)?, false))
+                Response::from_headers(&headers, false)
             } else {
                 Err(TwitterError(headers, terrs))
             }
@@ -145,7 +175,7 @@ pub async fn is_member<'id, T: Into<UserID>>(
                 // the rate limit info that would otherwise be part of the response isn't there. the
                 // rate_headers method was factored out specifically for this location, since it's
                 // still there, just accompanying an error response instead of a user.
-                Ok(Response::new(RateLimit::try_from(&headers)?, false))
+                Response::from_headers(&headers, false)
             } else {
                 Err(TwitterError(headers, errors))
             }
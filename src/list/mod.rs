@@ -162,6 +162,20 @@ pub struct List {
     ///UTC timestamp of when the list was created.
     #[serde(with = "serde_datetime")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    ///Whether the list can be seen by other users, or only by its creator.
+    pub mode: ListMode,
+}
+
+/// Represents whether a list can be seen by users other than its creator.
+///
+/// This can be set when a list is made with `create`, and changed later with `ListUpdate::public`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListMode {
+    /// The list can be seen and subscribed to by anyone.
+    Public,
+    /// The list can only be seen by its creator.
+    Private,
 }
 
 /// Represents a pending update to a list's metadata.
@@ -234,7 +248,7 @@ impl ListUpdate {
 
 #[cfg(test)]
 mod tests {
-    use super::List;
+    use super::{List, ListMode};
     use crate::common::tests::load_file;
 
     #[test]
@@ -242,6 +256,16 @@ mod tests {
         let content = load_file("sample_payloads/sample-list.json");
         let list = ::serde_json::from_str::<List>(&content).unwrap();
         assert_eq!(list.full_name, "@Scobleizer/all-people-in-spatial-2");
-        assert_eq!(list.user.screen_name, "Scobleizer")
+        assert_eq!(list.user.screen_name, "Scobleizer");
+        assert_eq!(list.mode, ListMode::Public);
+        assert_eq!(list.member_count, 265);
+        assert_eq!(list.subscriber_count, 6);
+    }
+
+    #[test]
+    fn parse_list_mode() {
+        let mode: ListMode = serde_json::from_str("\"private\"").unwrap();
+        assert_eq!(mode, ListMode::Private);
+        assert!(serde_json::from_str::<ListMode>("\"secret\"").is_err());
     }
 }
@@ -279,18 +279,29 @@ pub async fn upload_media_for_dm(
     finish_upload(media, data, token).await
 }
 
+/// The size of each piece of an upload sent with an `APPEND` command.
+const UPLOAD_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Splits the given data into chunks and returns the parameters of the `APPEND` command that sends
+/// each one, in order.
+fn append_params<'a>(media_id: &'a str, data: &'a [u8]) -> impl Iterator<Item = ParamList> + 'a {
+    data.chunks(UPLOAD_CHUNK_SIZE)
+        .enumerate()
+        .map(move |(ix, chunk)| {
+            ParamList::new()
+                .add_param("command", "APPEND")
+                .add_param("media_id", media_id.to_string())
+                .add_param("media_data", base64::encode(chunk))
+                .add_param("segment_index", ix.to_string())
+        })
+}
+
 async fn finish_upload(
     media: RawMedia,
     data: &[u8],
     token: &auth::Token,
 ) -> error::Result<MediaHandle> {
-    // divide into 1MB chunks
-    for (ix, chunk) in data.chunks(1024 * 1024).enumerate() {
-        let params = ParamList::new()
-            .add_param("command", "APPEND")
-            .add_param("media_id", media.id.clone())
-            .add_param("media_data", base64::encode(chunk))
-            .add_param("segment_index", ix.to_string());
+    for params in append_params(&media.id, data) {
         let req = post(links::media::UPLOAD, token, Some(&params));
         // This request has no response (upon success)
         raw_request(req).await?;
@@ -338,9 +349,39 @@ pub async fn set_metadata(
 
 #[cfg(test)]
 mod tests {
-    use super::RawMedia;
+    use super::{append_params, RawMedia, UPLOAD_CHUNK_SIZE};
     use crate::common::tests::load_file;
 
+    #[test]
+    fn upload_chunks() {
+        // a little over 5MB, so the last chunk is a partial one
+        let data = (0..5 * UPLOAD_CHUNK_SIZE + 17)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+        let chunks = append_params("710511363345354753", &data).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 6);
+
+        let mut rebuilt = Vec::new();
+        for (ix, params) in chunks.iter().enumerate() {
+            assert_eq!(params.get("command").map(|v| &**v), Some("APPEND"));
+            assert_eq!(
+                params.get("media_id").map(|v| &**v),
+                Some("710511363345354753")
+            );
+            assert_eq!(
+                params.get("segment_index").map(|v| &**v),
+                Some(&*ix.to_string())
+            );
+            let chunk = base64::decode(&*params["media_data"]).unwrap();
+            let expected = if ix < 5 { UPLOAD_CHUNK_SIZE } else { 17 };
+            assert_eq!(chunk.len(), expected);
+            rebuilt.extend(chunk);
+        }
+        assert_eq!(rebuilt, data);
+
+        assert_eq!(append_params("1", &[]).count(), 0);
+    }
+
     fn load_media(path: &str) -> RawMedia {
         let content = load_file(path);
         ::serde_json::from_str::<RawMedia>(&content).unwrap()
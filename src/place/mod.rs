@@ -115,13 +115,14 @@ impl<'de> Deserialize<'de> for SearchResult {
         let url = raw
             .get("query")
             .and_then(|obj| obj.get("url"))
+            .and_then(|url| url.as_str())
             .ok_or_else(|| D::Error::custom("Malformed search result"))?
             .to_string();
         let results = raw
             .get("result")
             .and_then(|obj| obj.get("places"))
-            .and_then(|arr| <Vec<Place>>::deserialize(arr).ok())
-            .ok_or_else(|| D::Error::custom("Malformed search result"))?;
+            .ok_or_else(|| D::Error::custom("Malformed search result"))
+            .and_then(|arr| <Vec<Place>>::deserialize(arr).map_err(D::Error::custom))?;
         Ok(SearchResult { url, results })
     }
 }
@@ -412,4 +413,29 @@ mod tests {
         let serialized_value = ::serde_json::to_value(&bounding_box).unwrap();
         assert_eq!(raw_value, serialized_value);
     }
+
+    #[test]
+    fn parse_reverse_geocode() {
+        let content = load_file("sample_payloads/reverse_geocode.json");
+        let result = ::serde_json::from_str::<SearchResult>(&content).unwrap();
+
+        assert_eq!(
+            result.url,
+            "https://api.twitter.com/1.1/geo/reverse_geocode.json?accuracy=0&granularity=neighborhood&lat=37.76893497&long=-122.42284884"
+        );
+        assert_eq!(result.results.len(), 2);
+
+        let place = &result.results[0];
+        assert_eq!(place.id, "5c92ab5379de3839");
+        assert_eq!(place.full_name, "Mission Dolores, San Francisco");
+        assert_eq!(place.country, "United States");
+        assert!(matches!(place.place_type, PlaceType::Neighborhood));
+        assert_eq!(place.bounding_box.len(), 4);
+        assert_eq!(place.bounding_box[0], (-122.42284884, 37.76893497));
+        let parent = &place.contained_within.as_ref().unwrap()[0];
+        assert_eq!(parent.full_name, "San Francisco, CA");
+        assert!(matches!(parent.place_type, PlaceType::City));
+
+        assert!(matches!(result.results[1].place_type, PlaceType::City));
+    }
 }
@@ -43,9 +43,9 @@
 //! the response:
 //!
 //! * At the most hands-off end, there's [`response_future`], which is a small wrapper that just
-//!   starts the request and hands off the `ResponseFuture` from `hyper` to give you the most power
-//!   over handling the response data.
-//! * In the middle, there's [`response_raw_bytes`], which wraps the `ResponseFuture` to return the
+//!   starts the request and hands off a future of the raw response from `hyper` to give you the
+//!   most power over handling the response data.
+//! * In the middle, there's [`response_raw_bytes`], which wraps that future to return the
 //!   headers and response body after inspecting the rate-limit headers and response code, and
 //!   after inspecting the response to see whether it returned error data from Twitter.
 //! * Finally there's [`response_json`], which picks up from `response_raw_bytes` to parse the
@@ -69,6 +69,14 @@
 //! see the [`auth`] module.
 //!
 //! [`auth`]: auth/index.html
+//!
+//! All of the requests egg-mode sends, including the ones made through this module, go through a
+//! single shared set of connection settings. If you need to change how egg-mode connects to
+//! Twitter, for example to have it use your own `hyper::Client`, see [`ConnectionConfig`]. To test
+//! code that uses egg-mode without connecting to Twitter, see [`MockTransport`].
+//!
+//! [`ConnectionConfig`]: struct.ConnectionConfig.html
+//! [`MockTransport`]: struct.MockTransport.html
 
 use hyper::{Body, Request};
 
@@ -80,6 +88,11 @@ use crate::tweet::Timeline as TweetTimeline;
 
 pub use crate::common::Headers;
 pub use crate::common::ParamList;
+pub use crate::common::{set_connection_config, ConnectionConfig, HttpsClient, HttpsConnector};
+pub use crate::common::{
+    with_transport, HttpTransport, MockRequest, MockTransport, TransportFuture,
+};
+pub use hyper::header::HeaderValue;
 
 pub use crate::auth::raw::delete as request_delete;
 pub use crate::auth::raw::get as request_get;
@@ -146,7 +159,7 @@ pub fn request_as_cursor_iter<T: cursor::Cursor + serde::de::DeserializeOwned>(
     params: Option<ParamList>,
     page_size: Option<i32>,
 ) -> cursor::CursorIter<T> {
-    cursor::CursorIter::new(url, token, params, page_size)
+    cursor::CursorIter::new(url, token, params, page_size, None)
 }
 
 pub use crate::common::get_response as response_future;
@@ -162,11 +175,14 @@ pub use crate::common::request_with_json_response as response_json;
 /// use other endpoints or options not available to `StreamBuilder`.
 ///
 /// Since the `TwitterStream` type doesn't need to provide additional parameters to the request, it
-/// can take a signed, completed request as its constructor.
+/// can take a signed, completed request as its constructor. A request can only be sent once,
+/// though, so a stream made this way can't reconnect, and `TwitterStream::reconnect` has no effect
+/// on it.
 pub fn response_as_stream(req: Request<Body>) -> TwitterStream {
-    TwitterStream::new(req)
+    TwitterStream::from_request(req)
 }
 
+pub use crate::common::JsonLineDecoder;
 pub use crate::common::RoundTrip;
 
 /// Facilities to manually assemble signed requests.
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Structs and functions for working with a user's saved searches.
+//!
+//! Twitter lets a user keep up to 25 search queries on their account, so they can run them again
+//! later from any client. This module lets you load, save, and delete those queries. A saved
+//! search only holds the query itself; to load its results, hand `SavedSearch::query` to
+//! `search::search`.
+//!
+//! ```rust,no_run
+//! # use egg_mode::Token;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let token: Token = unimplemented!();
+//! use egg_mode::{saved_search, search};
+//!
+//! for saved in saved_search::list(&token).await.unwrap().iter() {
+//!     let results = search::search(saved.query.clone()).call(&token).await.unwrap();
+//!     println!("{}: {} results", saved.name, results.statuses.len());
+//! }
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::error::Result;
+use crate::{auth, links};
+
+///A search query saved on a user's account.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedSearch {
+    ///The numeric ID of the saved search, used to load or delete it.
+    pub id: u64,
+    ///The name of the saved search. Twitter sets this to the query itself.
+    pub name: String,
+    ///The search query, in the same format as given to `search::search`.
+    pub query: String,
+    ///UTC timestamp of when the search was saved.
+    #[serde(with = "serde_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+///Loads the searches saved on the authenticated user's account.
+pub async fn list(token: &auth::Token) -> Result<Response<Vec<SavedSearch>>> {
+    let req = get(links::saved_searches::LIST, token, None);
+    request_with_json_response(req).await
+}
+
+///Loads the saved search with the given ID. The search must belong to the authenticated user.
+pub async fn show(id: u64, token: &auth::Token) -> Result<Response<SavedSearch>> {
+    let url = format!("{}/{}.json", links::saved_searches::SHOW_STEM, id);
+    let req = get(&url, token, None);
+    request_with_json_response(req).await
+}
+
+///Saves the given search query to the authenticated user's account.
+///
+///The query is given as-is to Twitter, in the same format as `search::search`. On success, the
+///future returned by this function yields the new saved search.
+pub async fn create<S: Into<CowStr>>(
+    query: S,
+    token: &auth::Token,
+) -> Result<Response<SavedSearch>> {
+    let params = ParamList::new().add_param("query", query.into());
+    let req = post(links::saved_searches::CREATE, token, Some(&params));
+    request_with_json_response(req).await
+}
+
+///Deletes the saved search with the given ID from the authenticated user's account.
+///
+///On success, the future returned by this function yields the search that was deleted.
+pub async fn destroy(id: u64, token: &auth::Token) -> Result<Response<SavedSearch>> {
+    let url = format!("{}/{}.json", links::saved_searches::DESTROY_STEM, id);
+    let req = post(&url, token, None);
+    request_with_json_response(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::load_file;
+    use crate::raw::{with_transport, MockTransport};
+
+    use chrono::{Datelike, Timelike};
+    use hyper::{Method, StatusCode};
+
+    #[test]
+    fn parse_saved_searches() {
+        let sample = load_file("sample_payloads/saved_searches.json");
+        let searches: Vec<SavedSearch> = serde_json::from_str(&sample).unwrap();
+
+        assert_eq!(searches.len(), 2);
+        assert_eq!(searches[0].id, 9569704);
+        assert_eq!(searches[0].name, "@twitterapi");
+        assert_eq!(searches[0].query, "@twitterapi");
+        assert_eq!(searches[0].created_at.year(), 2010);
+        assert_eq!(searches[0].created_at.month(), 6);
+        assert_eq!(searches[0].created_at.day(), 15);
+        assert_eq!(searches[0].created_at.hour(), 9);
+        assert_eq!(searches[1].query, "\"rust lang\" -filter:retweets");
+    }
+
+    #[tokio::test]
+    async fn create_params() {
+        let saved = load_file("sample_payloads/saved_searches.json");
+        let saved = serde_json::from_str::<Vec<serde_json::Value>>(&saved)
+            .unwrap()
+            .remove(1);
+        let mock = MockTransport::new().respond(StatusCode::OK, Headers::new(), saved.to_string());
+        let token = auth::Token::Bearer("abc".to_string());
+
+        let created = with_transport(
+            mock.clone(),
+            create("\"rust lang\" -filter:retweets", &token),
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.id, 62353170);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].uri.path(), "/1.1/saved_searches/create.json");
+        let body = String::from_utf8(requests[0].body.clone()).unwrap();
+        assert_eq!(body, "query=%22rust%20lang%22%20-filter%3Aretweets");
+    }
+}
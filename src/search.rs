@@ -33,22 +33,33 @@
 //! original query in the search result struct as well, so you can categorize multiple searches by
 //! their query. While this is given as a regular field, note that modifying `query` will not
 //! change what is searched for when you call `older` or `newer`; the `SearchResult` keeps its
-//! search arguments in a separate private field.
+//! search arguments in a separate private field. If you'd rather keep track of your place in the
+//! results yourself, the `next_results_query` and `refresh_query` strings from a `SearchResult`
+//! can be saved and handed to `from_next_results` to load those pages later. To read through the
+//! results without handling pages at all, `SearchBuilder::into_stream` gives a `Stream` of tweets
+//! that loads older pages as it goes.
 //!
 //! The search parameter given in the initial call to `search` has several options itself. A full
-//! reference is available in [Twitter's Search API documentation][search-doc]. This listing by
+//! reference is available in [Twitter's Search API documentation][search-doc]. That listing by
 //! itself does not include the search by Place ID, as mentioned on [a separate Tweets by Place
-//! page][search-place]. A future version of egg-mode might break these options into further
-//! methods on `SearchBuilder`.
+//! page][search-place]; `SearchBuilder::place` adds that operator to the query for you. A future
+//! version of egg-mode might break more of these options into further methods on
+//! `SearchBuilder`.
 //!
 //! [search-doc]: https://developer.twitter.com/en/docs/tweets/search/api-reference/get-search-tweets
 //! [search-place]: https://developer.twitter.com/en/docs/tweets/search/guides/tweets-by-place
 
+use std::collections::HashSet;
 use std::fmt;
+use std::future::Future;
 
-use serde::{Deserialize, Deserializer};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::common::*;
+use crate::stream::FilterLevel;
 use crate::tweet::Tweet;
 use crate::{auth, error, links};
 
@@ -57,15 +68,64 @@ pub fn search<S: Into<CowStr>>(query: S) -> SearchBuilder {
     SearchBuilder {
         query: query.into(),
         lang: None,
+        locale: None,
+        place: None,
         result_type: None,
         count: None,
         until: None,
         geocode: None,
         since_id: None,
         max_id: None,
+        filter_level: None,
+        include_entities: None,
     }
 }
 
+///Load a page of search results from a query string Twitter returned with an earlier search.
+///
+///Each `SearchResult` carries the query strings Twitter suggests for loading the next page of
+///older results and for polling for newer ones, available from `next_results_query` and
+///`refresh_query`. Those can be stored and handed to this function later to load that page, which
+///lets you track your place in a search yourself instead of holding on to the `SearchResult`. Any
+///query string with the parameters of a search will work, with or without a leading `?`.
+///
+///```rust,no_run
+///# use egg_mode::Token;
+///# #[tokio::main]
+///# async fn main() {
+///# let token: Token = unimplemented!();
+///use egg_mode::search;
+///
+///let page = search::search("rustlang").call(&token).await.unwrap();
+///if let Some(next) = page.next_results_query() {
+///    let older = search::from_next_results(next, &token).await.unwrap();
+///}
+///# }
+///```
+pub async fn from_next_results(
+    query: &str,
+    token: &auth::Token,
+) -> Result<Response<SearchResult>, error::Error> {
+    let params = url::form_urlencoded::parse(query.trim_start_matches('?').as_bytes())
+        .fold(ParamList::new(), |params, (key, value)| {
+            params.add_param(key.into_owned(), value.into_owned())
+        });
+
+    load_page(params.extended_tweets(), token).await
+}
+
+///Runs a search with the given parameters, keeping them in the result to load further pages.
+async fn load_page(
+    params: ParamList,
+    token: &auth::Token,
+) -> Result<Response<SearchResult>, error::Error> {
+    let req = get(links::statuses::SEARCH, token, Some(&params));
+    let mut resp = request_with_json_response::<SearchResult>(req).await?;
+
+    resp.response.params = Some(params);
+    Ok(resp)
+}
+
 ///Represents what kind of tweets should be included in search results.
 #[derive(Debug, Copy, Clone)]
 pub enum ResultType {
@@ -88,12 +148,34 @@ impl fmt::Display for ResultType {
     }
 }
 
+///The largest page size Twitter allows for a search, as given to `SearchBuilder::count`.
+pub const MAX_COUNT: u32 = 100;
+
 ///Represents a radius around a given location to return search results for.
+///
+///Twitter only accepts a radius in miles or kilometers, so `Meters` and `Feet` are converted to
+///kilometers and miles respectively before being sent.
 pub enum Distance {
     ///A radius given in miles.
     Miles(f32),
     ///A radius given in kilometers.
     Kilometers(f32),
+    ///A radius given in meters.
+    Meters(u32),
+    ///A radius given in feet.
+    Feet(u32),
+}
+
+///Display impl that turns a `Distance` into the radius part of a `geocode` parameter.
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Distance::Miles(r) => write!(f, "{}mi", r),
+            Distance::Kilometers(r) => write!(f, "{}km", r),
+            Distance::Meters(r) => write!(f, "{}km", f64::from(r) / 1000.0),
+            Distance::Feet(r) => write!(f, "{}mi", f64::from(r) / 5280.0),
+        }
+    }
 }
 
 ///Represents a tweet search query before being sent.
@@ -102,17 +184,24 @@ pub struct SearchBuilder {
     ///The text to search for.
     query: CowStr,
     lang: Option<CowStr>,
+    locale: Option<CowStr>,
+    place: Option<CowStr>,
     result_type: Option<ResultType>,
     count: Option<u32>,
     until: Option<(u32, u32, u32)>,
     geocode: Option<(f32, f32, Distance)>,
     since_id: Option<u64>,
     max_id: Option<u64>,
+    filter_level: Option<FilterLevel>,
+    include_entities: Option<bool>,
 }
 
 impl SearchBuilder {
     ///Restrict search results to those that have been machine-parsed as the given two-letter
     ///language code.
+    ///
+    ///This filters the tweets that come back. To say what language the query itself is written
+    ///in, use `locale` instead.
     pub fn lang<S: Into<CowStr>>(self, lang: S) -> Self {
         SearchBuilder {
             lang: Some(lang.into()),
@@ -120,7 +209,33 @@ impl SearchBuilder {
         }
     }
 
-    ///Specify the type of search results to include. The default is `Recent`.
+    ///Specify the language of the query itself, as a language code.
+    ///
+    ///Unlike `lang`, this doesn't filter the results. It tells Twitter how to split the query into
+    ///words, which matters for languages written without spaces between words. Twitter currently
+    ///only makes use of `ja`.
+    pub fn locale<S: Into<CowStr>>(self, locale: S) -> Self {
+        SearchBuilder {
+            locale: Some(locale.into()),
+            ..self
+        }
+    }
+
+    ///Restrict search results to tweets tagged with the given place, by its ID.
+    ///
+    ///Twitter doesn't take this as a separate parameter, so it's added to the end of the query as
+    ///a `place:<id>` operator, after a space, when the search is sent. It combines with the rest
+    ///of the query like any other search term, so `search("coffee").place("5a110d312052166f")`
+    ///searches for `coffee place:5a110d312052166f`. Calling this again replaces the place instead
+    ///of adding another one. Place IDs can be found with the functions in the `place` module.
+    pub fn place<S: Into<CowStr>>(self, place_id: S) -> Self {
+        SearchBuilder {
+            place: Some(place_id.into()),
+            ..self
+        }
+    }
+
+    ///Specify the type of search results to include. The default is `Mixed`.
     pub fn result_type(self, result_type: ResultType) -> Self {
         SearchBuilder {
             result_type: Some(result_type),
@@ -129,9 +244,12 @@ impl SearchBuilder {
     }
 
     ///Set the number of tweets to return per-page, up to a maximum of 100. The default is 15.
+    ///
+    ///Twitter returns an error for page sizes outside of 1 to 100, so values larger than
+    ///`MAX_COUNT` are lowered to it, and 0 is raised to 1.
     pub fn count(self, count: u32) -> Self {
         SearchBuilder {
-            count: Some(count),
+            count: Some(count.clamp(1, MAX_COUNT)),
             ..self
         }
     }
@@ -149,6 +267,10 @@ impl SearchBuilder {
     ///Restricts results to users located within the given radius of the given coordinate. This is
     ///preferably populated from location-tagged tweets, but can be filled in from the user's
     ///profile as a fallback.
+    ///
+    ///The latitude must be between -90 and 90, and the longitude between -180 and 180. If either
+    ///is out of range, loading the search returns an error of `InvalidCoordinate` without
+    ///contacting Twitter.
     pub fn geocode(self, latitude: f32, longitude: f32, radius: Distance) -> Self {
         SearchBuilder {
             geocode: Some((latitude, longitude, radius)),
@@ -158,6 +280,11 @@ impl SearchBuilder {
 
     ///Restricts results to those with higher IDs than (i.e. that were posted after) the given
     ///tweet ID.
+    ///
+    ///This is useful for picking up where an earlier search left off: save the highest tweet ID
+    ///it returned, and pass it here the next time to only load tweets that are newer. The bound
+    ///is kept when paging through the results with `SearchResult::older`, so paging stops at the
+    ///given tweet instead of running back into tweets that were already seen.
     pub fn since_tweet(self, since_id: u64) -> Self {
         SearchBuilder {
             since_id: Some(since_id),
@@ -174,16 +301,56 @@ impl SearchBuilder {
         }
     }
 
+    ///Restricts results to tweets that pass the given level of Twitter's quality filter. By
+    ///default, no filter level is sent, and Twitter applies its own default.
+    pub fn filter_level(self, filter_level: FilterLevel) -> Self {
+        SearchBuilder {
+            filter_level: Some(filter_level),
+            ..self
+        }
+    }
+
+    ///Sets whether Twitter should include the `entities` of each tweet in the results. Turning
+    ///this off makes the response smaller when you don't need the hashtags, links, mentions, or
+    ///media parsed out of tweets; their `entities` will then be empty. By default, this isn't
+    ///sent, and Twitter includes entities.
+    pub fn include_entities(self, include_entities: bool) -> Self {
+        SearchBuilder {
+            include_entities: Some(include_entities),
+            ..self
+        }
+    }
+
     ///Finalize the search terms and return the first page of responses.
     pub async fn call(self, token: &auth::Token) -> Result<Response<SearchResult>, error::Error> {
+        load_page(self.into_params()?, token).await
+    }
+
+    ///Assembles the parameters to send for this search, after checking that they're valid.
+    fn into_params(self) -> Result<ParamList, error::Error> {
+        if let Some((lat, lon, _)) = self.geocode {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return Err(error::Error::InvalidCoordinate(lat, lon));
+            }
+        }
+
+        let query = match self.place {
+            Some(place) if self.query.trim().is_empty() => format!("place:{}", place).into(),
+            Some(place) => format!("{} place:{}", self.query.trim_end(), place).into(),
+            None => self.query,
+        };
+
         let params = ParamList::new()
             .extended_tweets()
-            .add_param("q", self.query)
+            .add_param("q", query)
             .add_opt_param("lang", self.lang)
+            .add_opt_param("locale", self.locale)
             .add_opt_param("result_type", self.result_type.map_string())
             .add_opt_param("count", self.count.map_string())
             .add_opt_param("since_id", self.since_id.map_string())
             .add_opt_param("max_id", self.max_id.map_string())
+            .add_opt_param("filter_level", self.filter_level.map_string())
+            .add_opt_param("include_entities", self.include_entities.map_string())
             .add_opt_param(
                 "until",
                 self.until
@@ -191,27 +358,149 @@ impl SearchBuilder {
             )
             .add_opt_param(
                 "geocode",
-                self.geocode.map(|(lat, lon, radius)| match radius {
-                    Distance::Miles(r) => format!("{:.6},{:.6},{}mi", lat, lon, r),
-                    Distance::Kilometers(r) => format!("{:.6},{:.6},{}km", lat, lon, r),
-                }),
+                self.geocode
+                    .map(|(lat, lon, radius)| format!("{:.6},{:.6},{}", lat, lon, radius)),
             );
 
-        let req = get(links::statuses::SEARCH, token, Some(&params));
-        let mut resp = request_with_json_response::<SearchResult>(req).await?;
+        Ok(params)
+    }
+
+    ///Load up to `count` unique tweets for this search, returned oldest-first.
+    ///
+    ///This loads the first page with `call`, then keeps loading older pages as with
+    ///`SearchResult::older` until it has `count` tweets or Twitter runs out of results. Since the
+    ///standard search API only covers about the past week, a search for a busy term may have more
+    ///than `count` results while a quieter one runs out early; either way, the tweets loaded so
+    ///far are returned. Tweets that appear on more than one page are only returned once.
+    ///
+    ///Since the newest results are loaded first, the returned tweets are the newest `count` that
+    ///matched, sorted by ID so that the oldest comes first. The rate-limit information is from the
+    ///last page that was loaded.
+    pub async fn collect(
+        self,
+        count: usize,
+        token: &auth::Token,
+    ) -> Result<Response<Vec<Tweet>>, error::Error> {
+        let mut seen = HashSet::new();
+        let mut tweets = Vec::new();
+        let mut page = self.call(token).await?;
+
+        loop {
+            let next = page
+                .response
+                .next_results
+                .as_ref()
+                .map(|_| page.response.older_params());
+            for tweet in page.response.statuses.drain(..) {
+                if tweets.len() < count && seen.insert(tweet.id) {
+                    tweets.push(tweet);
+                }
+            }
+
+            if tweets.len() >= count {
+                break;
+            }
+
+            page = match next {
+                Some(params) => load_page(params, token).await?,
+                None => break,
+            };
+            if page.response.statuses.is_empty() {
+                break;
+            }
+        }
+
+        tweets.sort_by_key(|tweet| tweet.id);
+        Ok(Response::map(page, |_| tweets))
+    }
+
+    ///Converts this search into a `Stream` of tweets, which automatically loads older pages as
+    ///needed.
+    ///
+    ///The stream starts with the first page of results, as returned by `call`, and yields its
+    ///tweets one at a time, newest first. Once a page runs out, the next one is loaded as with
+    ///`SearchResult::older`. The stream ends when Twitter returns an empty page or stops giving a
+    ///`next_results` for more. Each tweet carries the rate-limit information from the page it was
+    ///loaded with.
+    ///
+    ///To stop after a certain number of tweets, use `take` on the stream; pages after the one
+    ///holding the last tweet taken won't be loaded.
+    ///
+    ///```rust,no_run
+    ///# use egg_mode::Token;
+    ///# #[tokio::main]
+    ///# async fn main() {
+    ///# let token: Token = unimplemented!();
+    ///use egg_mode::search;
+    ///use futures::{StreamExt, TryStreamExt};
+    ///
+    ///let tweets = search::search("rustlang")
+    ///    .into_stream(&token)
+    ///    .take(250)
+    ///    .try_collect::<Vec<_>>()
+    ///    .await
+    ///    .unwrap();
+    ///# }
+    ///```
+    pub fn into_stream(
+        self,
+        token: &auth::Token,
+    ) -> impl Stream<Item = Result<Response<Tweet>, error::Error>> {
+        let params = match self.into_params() {
+            Ok(params) => params,
+            Err(e) => return stream::once(future::ready(Err(e))).left_stream(),
+        };
 
-        resp.response.params = Some(params);
-        Ok(resp)
+        let token = token.clone();
+        paginate(params, move |params| {
+            let token = token.clone();
+            async move { load_page(params, &token).await }
+        })
+        .right_stream()
     }
 }
 
+///Loads pages of search results with `load`, starting with the given parameters and moving to
+///older pages until there are no more, and yields their tweets one at a time.
+fn paginate<F, Fut>(
+    params: ParamList,
+    mut load: F,
+) -> impl Stream<Item = Result<Response<Tweet>, error::Error>>
+where
+    F: FnMut(ParamList) -> Fut,
+    Fut: Future<Output = Result<Response<SearchResult>, error::Error>>,
+{
+    stream::try_unfold(Some(params), move |params| {
+        let page = params.map(&mut load);
+        async move {
+            let mut page = match page {
+                Some(page) => page.await?,
+                None => return Ok::<_, error::Error>(None),
+            };
+            if page.statuses.is_empty() {
+                return Ok(None);
+            }
+
+            let next = page.next_results.as_ref().map(|_| page.older_params());
+            let statuses = std::mem::take(&mut page.response.statuses);
+            Ok(Some((Response::map(page, |_| statuses), next)))
+        }
+    })
+    .map_ok(|page| stream::iter(page).map(Ok::<_, error::Error>))
+    .try_flatten()
+}
+
 #[derive(Debug, Deserialize)]
 struct RawSearch {
     search_metadata: RawSearchMetaData,
     statuses: Vec<Tweet>,
+    /// not sent by Twitter, but saved when a `SearchResult` is serialized, so that a reloaded
+    /// result can still load the pages around it
+    #[serde(default)]
+    params: Option<ParamList>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct RawSearchMetaData {
     completed_in: f64,
     max_id: u64,
@@ -235,12 +524,43 @@ impl<'de> Deserialize<'de> for SearchResult {
             query: raw.search_metadata.query,
             max_id: raw.search_metadata.max_id,
             since_id: raw.search_metadata.since_id,
-            params: None,
+            completed_in: raw.search_metadata.completed_in,
+            count: raw.search_metadata.count as u32,
+            next_results: raw.search_metadata.next_results,
+            refresh_url: raw.search_metadata.refresh_url,
+            params: raw.params,
         })
     }
 }
 
+impl Serialize for SearchResult {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let metadata = RawSearchMetaData {
+            completed_in: self.completed_in,
+            max_id: self.max_id,
+            next_results: self.next_results.clone(),
+            query: self.query.clone(),
+            refresh_url: self.refresh_url.clone(),
+            count: self.count.into(),
+            since_id: self.since_id,
+        };
+
+        let mut out = ser.serialize_struct("SearchResult", 3)?;
+        out.serialize_field("search_metadata", &metadata)?;
+        out.serialize_field("statuses", &self.statuses)?;
+        out.serialize_field("params", &self.params)?;
+        out.end()
+    }
+}
+
 ///Represents a page of search results, along with metadata to request the next or previous page.
+///
+///A `SearchResult` can be saved with serde and loaded again later. It's written in the same format
+///Twitter sends it in, along with the parameters of the search, so `older` and `newer` still work
+///on a result that was loaded back in.
 #[derive(Debug)]
 pub struct SearchResult {
     ///The list of statuses in this page of results.
@@ -252,12 +572,88 @@ pub struct SearchResult {
     pub max_id: u64,
     ///First tweet id in this page of results. This id can be used in `SearchBuilder::since_tweet`
     pub since_id: u64,
+    completed_in: f64,
+    count: u32,
+    next_results: Option<String>,
+    refresh_url: Option<String>,
     params: Option<ParamList>,
 }
 
 impl SearchResult {
+    ///Returns how long Twitter took to perform this search, in seconds.
+    pub fn completed_in(&self) -> f64 {
+        self.completed_in
+    }
+
+    ///Returns the page size Twitter used for this search. This reflects the `count` given to
+    ///`SearchBuilder`, or Twitter's default if none was given, rather than the number of tweets in
+    ///`statuses`.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    ///Returns whether there's another page of results older than this one.
+    ///
+    ///This uses whether Twitter returned a `next_results` query string, which it leaves out once
+    ///a search has run out of results. An empty page always returns `false`. If this returns
+    ///`false`, calling `older` will only return an empty page.
+    pub fn has_older(&self) -> bool {
+        !self.statuses.is_empty() && self.next_results.is_some()
+    }
+
+    ///Returns the ID of the oldest tweet in this page, or `None` if the page is empty.
+    ///
+    ///Unlike the `since_id` field, which echoes the bound the search was made with, this is taken
+    ///from the tweets themselves, so it's the boundary `older` will load from.
+    pub fn oldest_id(&self) -> Option<u64> {
+        self.statuses.iter().map(|t| t.id).min()
+    }
+
+    ///Returns the ID of the newest tweet in this page, or `None` if the page is empty.
+    ///
+    ///This is the ID to save with `SearchBuilder::since_tweet` to pick up where this search left
+    ///off, and the boundary `newer` will load from.
+    pub fn newest_id(&self) -> Option<u64> {
+        self.statuses.iter().map(|t| t.id).max()
+    }
+
+    ///Returns the query string Twitter recommends for loading the page of results older than
+    ///this one, if there is one.
+    ///
+    ///This is given as a raw query string (like `?max_id=...&q=...`) to be appended to the search
+    ///URL. Twitter omits it when there are no more results. It can be handed to
+    ///`search::from_next_results` to load that page later. To load the next page right away, use
+    ///`older` instead.
+    pub fn next_results_query(&self) -> Option<&str> {
+        self.next_results.as_deref()
+    }
+
+    ///Returns the query string Twitter recommends for polling tweets newer than this page, if it
+    ///returned one.
+    ///
+    ///This is given as a raw query string (like `?since_id=...&q=...`) to be appended to the
+    ///search URL. Twitter omits it when a search returns no results. It can be handed to
+    ///`search::from_next_results` to load newer results later, or you can use `newer`, which sends
+    ///the same `since_id`.
+    pub fn refresh_query(&self) -> Option<&str> {
+        self.refresh_url.as_deref()
+    }
+
     ///Load the next page of search results for the same query.
+    ///
+    ///If the search was started with `SearchBuilder::since_tweet`, or this page was loaded with
+    ///`newer`, the older page stays limited to tweets after that ID.
     pub async fn older(&self, token: &auth::Token) -> Result<Response<SearchResult>, error::Error> {
+        load_page(self.older_params(), token).await
+    }
+
+    ///Load the previous page of search results for the same query.
+    pub async fn newer(&self, token: &auth::Token) -> Result<Response<SearchResult>, error::Error> {
+        load_page(self.newer_params(), token).await
+    }
+
+    ///Assembles the parameters to load the page of results older than this one.
+    fn older_params(&self) -> ParamList {
         let mut params = self
             .params
             .as_ref()
@@ -265,23 +661,17 @@ impl SearchResult {
             .unwrap_or_default()
             .extended_tweets();
 
-        params.remove("since_id");
-
-        if let Some(min_id) = self.statuses.iter().map(|t| t.id).min() {
+        if let Some(min_id) = self.oldest_id() {
             params.add_param_ref("max_id", (min_id - 1).to_string());
         } else {
             params.remove("max_id");
         }
 
-        let req = get(links::statuses::SEARCH, token, Some(&params));
-        let mut resp = request_with_json_response::<SearchResult>(req).await?;
-
-        resp.response.params = Some(params);
-        Ok(resp)
+        params
     }
 
-    ///Load the previous page of search results for the same query.
-    pub async fn newer(&self, token: &auth::Token) -> Result<Response<SearchResult>, error::Error> {
+    ///Assembles the parameters to load the page of results newer than this one.
+    fn newer_params(&self) -> ParamList {
         let mut params = self
             .params
             .as_ref()
@@ -290,16 +680,361 @@ impl SearchResult {
             .extended_tweets();
 
         params.remove("max_id");
-        if let Some(max_id) = self.statuses.iter().map(|t| t.id).max() {
+        if let Some(max_id) = self.newest_id() {
             params.add_param_ref("since_id", max_id.to_string());
         } else {
             params.remove("since_id");
         }
 
-        let req = get(links::statuses::SEARCH, token, Some(&params));
-        let mut resp = request_with_json_response::<SearchResult>(req).await?;
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search, SearchResult, MAX_COUNT};
+    use crate::common::tests::load_file;
+    use crate::tweet::Tweet;
+
+    ///Builds a page of results for a search for "rustlang", with no parameters to load the pages
+    ///around it.
+    fn result(statuses: Vec<Tweet>, next_results: Option<&str>) -> SearchResult {
+        SearchResult {
+            statuses,
+            query: "rustlang".into(),
+            max_id: 0,
+            since_id: 0,
+            completed_in: 0.0,
+            count: 15,
+            next_results: next_results.map(String::from),
+            refresh_url: None,
+            params: None,
+        }
+    }
+
+    #[test]
+    fn clamp_count() {
+        assert_eq!(search("rustlang").count(0).count, Some(1));
+        assert_eq!(search("rustlang").count(100).count, Some(100));
+        assert_eq!(search("rustlang").count(1000).count, Some(MAX_COUNT));
+        assert_eq!(search("rustlang").count, None);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let statuses: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        let mut page = result(statuses, Some("?max_id=1&q=rustlang"));
+        page.completed_in = 0.05;
+        page.params = Some(search("rustlang").into_params().unwrap());
+
+        let json = serde_json::to_value(&page).unwrap();
+        assert_eq!(json["search_metadata"]["query"], "rustlang");
+        let loaded: SearchResult = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), json);
+
+        assert_eq!(loaded.statuses.len(), page.statuses.len());
+        assert_eq!(loaded.count(), 15);
+        assert!(loaded.has_older());
+        assert_eq!(*loaded.older_params(), *page.older_params());
+        assert_eq!(
+            loaded.older_params().get("q").map(|v| &**v),
+            Some("rustlang")
+        );
+    }
+
+    #[test]
+    fn lang_and_locale() {
+        let params = search("rustlang").into_params().unwrap();
+        assert!(params.get("lang").is_none());
+        assert!(params.get("locale").is_none());
+
+        let params = search("東京").locale("ja").into_params().unwrap();
+        assert_eq!(params.get("locale").map(|v| &**v), Some("ja"));
+        assert!(params.get("lang").is_none());
+
+        let params = search("東京")
+            .lang("en")
+            .locale("ja")
+            .into_params()
+            .unwrap();
+        assert_eq!(params.get("lang").map(|v| &**v), Some("en"));
+        assert_eq!(params.get("locale").map(|v| &**v), Some("ja"));
+    }
+
+    #[test]
+    fn place_query() {
+        let q = |builder: super::SearchBuilder| {
+            let params = builder.into_params().unwrap();
+            params.get("q").unwrap().to_string()
+        };
+
+        assert_eq!(q(search("coffee")), "coffee");
+        assert_eq!(
+            q(search("coffee").place("5a110d312052166f")),
+            "coffee place:5a110d312052166f"
+        );
+        assert_eq!(
+            q(search("coffee -decaf ").place("5a110d312052166f")),
+            "coffee -decaf place:5a110d312052166f"
+        );
+        assert_eq!(
+            q(search("").place("5a110d312052166f")),
+            "place:5a110d312052166f"
+        );
+        assert_eq!(
+            q(search("coffee").place("1").place("5a110d312052166f")),
+            "coffee place:5a110d312052166f"
+        );
+
+        let params = search("coffee")
+            .place("5a110d312052166f")
+            .into_params()
+            .unwrap();
+        assert!(params
+            .to_urlencoded()
+            .split('&')
+            .any(|p| p == "q=coffee%20place%3A5a110d312052166f"));
+        assert!(params.get("place").is_none());
+    }
+
+    #[test]
+    fn resume_bounds() {
+        let params = search("rustlang")
+            .since_tweet(1000)
+            .max_tweet(5000)
+            .into_params()
+            .unwrap();
+        assert_eq!(params.get("since_id").map(|v| &**v), Some("1000"));
+        assert_eq!(params.get("max_id").map(|v| &**v), Some("5000"));
+
+        let params = search("rustlang").into_params().unwrap();
+        assert!(params.get("since_id").is_none());
+        assert!(params.get("max_id").is_none());
+
+        let statuses: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        let min_id = statuses.iter().map(|t| t.id).min().unwrap();
+        let max_id = statuses.iter().map(|t| t.id).max().unwrap();
+        let mut page = result(statuses, None);
+        page.max_id = max_id;
+        page.since_id = 1000;
+        page.params = Some(search("rustlang").since_tweet(1000).into_params().unwrap());
+
+        let older = page.older_params();
+        assert_eq!(older.get("since_id").map(|v| &**v), Some("1000"));
+        assert_eq!(
+            older.get("max_id").map(|v| v.to_string()),
+            Some((min_id - 1).to_string())
+        );
+
+        let newer = page.newer_params();
+        assert_eq!(
+            newer.get("since_id").map(|v| v.to_string()),
+            Some(max_id.to_string())
+        );
+        assert!(newer.get("max_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_pages() {
+        use super::paginate;
+        use crate::common::{RateLimit, Response};
+        use futures::TryStreamExt;
+
+        let mut tweets: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        tweets.sort_by_key(|t| std::cmp::Reverse(t.id));
+        let second = tweets.split_off(tweets.len() / 2);
+        let first = tweets;
+        let boundary = first.iter().map(|t| t.id).min().unwrap() - 1;
+        let expected = first
+            .iter()
+            .chain(&second)
+            .map(|t| t.id)
+            .collect::<Vec<_>>();
+
+        let mut pages = vec![first, second, vec![]].into_iter();
+        let mut calls = Vec::new();
+        let stream = paginate(search("rustlang").into_params().unwrap(), |params| {
+            calls.push(params.get("max_id").map(|v| v.to_string()));
+            let page = Response::new(
+                RateLimit {
+                    limit: 180,
+                    remaining: 180 - calls.len() as i32,
+                    reset: -1,
+                },
+                SearchResult {
+                    params: Some(params),
+                    ..result(pages.next().unwrap(), Some(""))
+                },
+            );
+            async move { Ok(page) }
+        });
+
+        let loaded = stream.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(loaded.iter().map(|t| t.id).collect::<Vec<_>>(), expected);
+        assert_eq!(loaded[0].rate_limit_status.remaining, 179);
+        assert_eq!(loaded.last().unwrap().rate_limit_status.remaining, 178);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0], None);
+        assert_eq!(calls[1], Some(boundary.to_string()));
+    }
+
+    #[tokio::test]
+    async fn collect_pages() {
+        use crate::auth::Token;
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use hyper::StatusCode;
+
+        let mut tweets: Vec<serde_json::Value> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        tweets.sort_by_key(|t| std::cmp::Reverse(t["id"].as_u64().unwrap()));
+        let second = tweets.split_off(tweets.len() / 2);
+        let boundary = tweets.last().unwrap()["id"].as_u64().unwrap() - 1;
+        let total = tweets.len() + second.len();
+        let page = |statuses: Vec<serde_json::Value>, next_results: Option<&str>| {
+            serde_json::json!({
+                "statuses": statuses,
+                "search_metadata": {
+                    "completed_in": 0.0,
+                    "max_id": 0,
+                    "next_results": next_results,
+                    "query": "rustlang",
+                    "count": 15,
+                    "since_id": 1000,
+                },
+            })
+            .to_string()
+        };
+
+        //the next_results Twitter sends is ignored in favor of the search's own parameters
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::OK,
+                Headers::new(),
+                page(tweets, Some("?max_id=1&q=something%20else")),
+            )
+            .respond(StatusCode::OK, Headers::new(), page(second, None));
+        let token = Token::Bearer("abc".to_string());
+
+        let loaded = with_transport(
+            mock.clone(),
+            search("rustlang").since_tweet(1000).collect(1000, &token),
+        )
+        .await
+        .unwrap();
+        assert_eq!(loaded.len(), total);
+        assert!(loaded.windows(2).all(|w| w[0].id < w[1].id));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        let query = requests[1].uri.query().unwrap();
+        assert!(query.contains("q=rustlang"));
+        assert!(query.contains("since_id=1000"));
+        assert!(query.contains(&format!("max_id={}", boundary)));
+    }
+
+    #[test]
+    fn filter_level_entities() {
+        use crate::stream::FilterLevel;
+
+        assert_eq!(FilterLevel::None.to_string(), "none");
+        assert_eq!(FilterLevel::Low.to_string(), "low");
+        assert_eq!(FilterLevel::Medium.to_string(), "medium");
+
+        let params = search("rustlang").into_params().unwrap();
+        assert!(params.get("filter_level").is_none());
+        assert!(params.get("include_entities").is_none());
+
+        let params = search("rustlang")
+            .filter_level(FilterLevel::Medium)
+            .include_entities(false)
+            .into_params()
+            .unwrap();
+        assert_eq!(params.get("filter_level").map(|v| &**v), Some("medium"));
+        assert_eq!(params.get("include_entities").map(|v| &**v), Some("false"));
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-quote.json")).unwrap();
+        json.as_object_mut().unwrap().remove("entities");
+        let tweet: Tweet = serde_json::from_value(json).unwrap();
+        assert!(tweet.entities.urls.is_empty());
+    }
+
+    #[test]
+    fn send_to_thread() {
+        fn assert_send_static<T: Send + 'static>(_: &T) {}
+
+        let statuses: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        let min_id = statuses.iter().map(|t| t.id).min().unwrap();
+        let mut page = result(statuses, None);
+        page.params = Some(search(String::from("rustlang")).into_params().unwrap());
+        assert_send_static(&page);
+
+        let older = std::thread::spawn(move || page.older_params())
+            .join()
+            .unwrap();
+        assert_eq!(older.get("q").map(|v| &**v), Some("rustlang"));
+        assert_eq!(
+            older.get("max_id").map(|v| v.to_string()),
+            Some((min_id - 1).to_string())
+        );
+    }
+
+    #[test]
+    fn page_boundaries() {
+        let statuses: Vec<Tweet> =
+            serde_json::from_str(&load_file("sample_payloads/tweet_array.json")).unwrap();
+        let min_id = statuses.iter().map(|t| t.id).min().unwrap();
+        let max_id = statuses.iter().map(|t| t.id).max().unwrap();
+        let full = result(statuses.clone(), Some("?max_id=1&q=rustlang"));
+        assert!(full.has_older());
+        assert_eq!(full.oldest_id(), Some(min_id));
+        assert_eq!(full.newest_id(), Some(max_id));
+
+        let partial = result(statuses, None);
+        assert!(!partial.has_older());
+        assert_eq!(partial.oldest_id(), Some(min_id));
+
+        let empty = result(vec![], Some("?max_id=1&q=rustlang"));
+        assert!(!empty.has_older());
+        assert_eq!(empty.oldest_id(), None);
+        assert_eq!(empty.newest_id(), None);
+    }
+
+    #[test]
+    fn geocode_units() {
+        use super::Distance;
+
+        assert_eq!(Distance::Miles(1.5).to_string(), "1.5mi");
+        assert_eq!(Distance::Kilometers(10.0).to_string(), "10km");
+        assert_eq!(Distance::Meters(1500).to_string(), "1.5km");
+        assert_eq!(Distance::Feet(2640).to_string(), "0.5mi");
+
+        let params = search("rustlang")
+            .geocode(37.5, -122.25, Distance::Meters(500))
+            .into_params()
+            .unwrap();
+        assert_eq!(
+            params.get("geocode").map(|v| &**v),
+            Some("37.500000,-122.250000,0.5km")
+        );
 
-        resp.response.params = Some(params);
-        Ok(resp)
+        let res = search("rustlang")
+            .geocode(91.0, 0.0, Distance::Miles(1.0))
+            .into_params();
+        assert!(matches!(
+            res,
+            Err(crate::error::Error::InvalidCoordinate(lat, _)) if lat == 91.0
+        ));
+        let res = search("rustlang")
+            .geocode(0.0, -180.5, Distance::Miles(1.0))
+            .into_params();
+        assert!(matches!(
+            res,
+            Err(crate::error::Error::InvalidCoordinate(..))
+        ));
     }
 }
@@ -94,6 +94,41 @@ pub async fn rate_limit_status(token: &auth::Token) -> Result<Response<RateLimit
     request_with_json_response(req).await
 }
 
+///Checks whether the authenticated user has enough rate limit left to make the given calls.
+///
+///Each entry in `plan` is an endpoint, given as the path Twitter uses for it in
+///`rate_limit_status` (like `"/statuses/user_timeline"` or `"/users/show/:id"`), along with how
+///many times you intend to call it. This loads the current rate-limit status and compares it
+///against the plan; see [`AffordReport`][] for what it reports.
+///
+///Note that loading the rate-limit status is itself a call to
+///`/application/rate_limit_status`, which is rate-limited like any other.
+///
+///[`AffordReport`]: struct.AffordReport.html
+///
+///```rust,no_run
+///# use egg_mode::Token;
+///# #[tokio::main]
+///# async fn main() {
+///# let token: Token = unimplemented!();
+///let plan = [("/statuses/user_timeline", 50), ("/users/lookup", 10)];
+///let report = egg_mode::service::can_afford(&plan, &token).await.unwrap();
+///
+///if !report.is_affordable() {
+///    for shortfall in &report.shortfalls {
+///        println!("not enough calls left for {}", shortfall.endpoint);
+///    }
+///}
+///# }
+///```
+pub async fn can_afford(
+    plan: &[(&str, u32)],
+    token: &auth::Token,
+) -> Result<Response<AffordReport>> {
+    let status = rate_limit_status(token).await?;
+    Ok(Response::map(status, |status| status.afford(plan)))
+}
+
 ///Like `rate_limit_status`, but returns the raw JSON without processing it. Only intended to
 ///return the full structure so that new methods can be added to `RateLimitStatus` and its
 ///associated enums.
@@ -185,6 +220,96 @@ pub struct RateLimitStatus {
     pub list: HashMap<ListMethod, Response<()>>,
 }
 
+impl RateLimitStatus {
+    ///Compares the given plan of calls against this rate-limit status. See [`can_afford`][] for
+    ///the format of `plan`.
+    ///
+    ///[`can_afford`]: fn.can_afford.html
+    pub fn afford(&self, plan: &[(&str, u32)]) -> AffordReport {
+        let mut planned: Vec<(&str, u32)> = Vec::new();
+        for &(endpoint, calls) in plan {
+            match planned.iter_mut().find(|(e, _)| *e == endpoint) {
+                Some((_, total)) => *total += calls,
+                None => planned.push((endpoint, calls)),
+            }
+        }
+
+        let mut report = AffordReport {
+            shortfalls: Vec::new(),
+            unknown: Vec::new(),
+        };
+
+        for (endpoint, calls) in planned {
+            let status = match endpoint.parse::<Method>() {
+                Ok(method) => self.get(method),
+                Err(()) => None,
+            };
+
+            match status {
+                Some(status) => {
+                    let status = status.rate_limit_status;
+                    if (status.remaining as i64) < calls as i64 {
+                        report.shortfalls.push(Shortfall {
+                            endpoint: endpoint.to_string(),
+                            planned: calls,
+                            remaining: status.remaining,
+                            reset: status.reset,
+                        });
+                    }
+                }
+                None => report.unknown.push(endpoint.to_string()),
+            }
+        }
+
+        report
+    }
+
+    fn get(&self, method: Method) -> Option<&Response<()>> {
+        match method {
+            Method::Direct(m) => self.direct.get(&m),
+            Method::Place(m) => self.place.get(&m),
+            Method::Search(m) => self.search.get(&m),
+            Method::Service(m) => self.service.get(&m),
+            Method::Tweet(m) => self.tweet.get(&m),
+            Method::User(m) => self.user.get(&m),
+            Method::List(m) => self.list.get(&m),
+        }
+    }
+}
+
+///The result of checking a plan of calls against the current rate limits, returned by
+///`can_afford`.
+#[derive(Debug, Clone)]
+pub struct AffordReport {
+    ///The endpoints that don't have enough calls left in the current window to carry out the plan.
+    pub shortfalls: Vec<Shortfall>,
+    ///The endpoints in the plan that Twitter didn't report a rate limit for, either because the
+    ///path wasn't recognized or because the endpoint isn't listed by `rate_limit_status`. These
+    ///aren't counted as shortfalls, since their limits couldn't be checked.
+    pub unknown: Vec<String>,
+}
+
+impl AffordReport {
+    ///Returns whether every endpoint that could be checked has enough calls left for the plan.
+    pub fn is_affordable(&self) -> bool {
+        self.shortfalls.is_empty()
+    }
+}
+
+///An endpoint that doesn't have enough calls left for a plan given to `can_afford`.
+#[derive(Debug, Clone)]
+pub struct Shortfall {
+    ///The endpoint, as given in the plan.
+    pub endpoint: String,
+    ///The number of calls planned for this endpoint. If it was listed more than once in the plan,
+    ///this is the total.
+    pub planned: u32,
+    ///The number of calls left in the current window.
+    pub remaining: i32,
+    ///The UTC Unix timestamp at which the current window resets.
+    pub reset: i32,
+}
+
 impl<'de> Deserialize<'de> for RateLimitStatus {
     fn deserialize<D>(ser: D) -> StdResult<Self, D::Error>
     where
@@ -482,4 +607,27 @@ mod tests {
         let sample = load_file("sample_payloads/rate_limit_sample.json");
         ::serde_json::from_str::<RateLimitStatus>(&sample).unwrap();
     }
+
+    #[test]
+    fn afford_plan() {
+        let sample = load_file("sample_payloads/rate_limit_sample.json");
+        let status = ::serde_json::from_str::<RateLimitStatus>(&sample).unwrap();
+
+        let report = status.afford(&[("/statuses/user_timeline", 900), ("/users/show/:id", 10)]);
+        assert!(report.is_affordable());
+        assert!(report.unknown.is_empty());
+
+        let report = status.afford(&[
+            ("/search/tweets", 100),
+            ("/search/tweets", 100),
+            ("/not/an/endpoint", 1),
+        ]);
+        assert!(!report.is_affordable());
+        assert_eq!(report.shortfalls.len(), 1);
+        assert_eq!(report.shortfalls[0].endpoint, "/search/tweets");
+        assert_eq!(report.shortfalls[0].planned, 200);
+        assert_eq!(report.shortfalls[0].remaining, 180);
+        assert_eq!(report.shortfalls[0].reset, 1517316252);
+        assert_eq!(report.unknown, vec!["/not/an/endpoint".to_string()]);
+    }
 }
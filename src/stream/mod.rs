@@ -39,7 +39,7 @@
 //! such that after (say) 1 minute of inactivity, the client bounces the connection. This will protect
 //! against network stalls
 //! * Twitter will rate-limit reconnect attempts. So attempt conenctions with a linear or exponential
-//! backoff strategy
+//! backoff strategy. `TwitterStream::reconnect` takes a `Backoff` and does this for you
 //! * In the case of an unreliable connection (e.g. mobile network), fall back to the polling API
 //!
 //! The [official guide](https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/connecting) has more information.
@@ -50,15 +50,16 @@ use std::task::{Context, Poll};
 use std::{self, io};
 
 use futures::Stream;
-use hyper::client::ResponseFuture;
 use hyper::{Body, Request};
-use serde::de::Error;
+use serde::de::{DeserializeOwned, Error};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json;
 
 use crate::auth::Token;
 use crate::common::*;
+use crate::list::List;
 use crate::tweet::Tweet;
+use crate::user::TwitterUser;
 use crate::{error, links};
 
 // TODO rewrite this
@@ -131,6 +132,14 @@ pub enum StreamMessage {
     ///
     /// [stream-doc]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
     Disconnect(u64, String),
+    /// A notification about an action taken by or against the authenticated user, such as a
+    /// like, a follow, or a change to one of their lists.
+    ///
+    /// These are delivered on user streams and by the Account Activity API. See the documentation
+    /// for [`StreamEvent`] for the events egg-mode recognizes.
+    ///
+    /// [`StreamEvent`]: enum.StreamEvent.html
+    Event(StreamEvent),
     /// An unhandled message payload.
     ///
     /// Twitter can add new streaming messages to the API, and egg-mode includes them here so that
@@ -182,6 +191,21 @@ impl<'de> Deserialize<'de> for StreamMessage {
                 serde_json::from_value(friends.clone())
                     .map_err(|e| D::Error::custom(format!("{}", e)))?,
             )
+        } else if let Some(friends) = input.get("friends_str") {
+            let friends: Vec<String> = serde_json::from_value(friends.clone())
+                .map_err(|e| D::Error::custom(format!("{}", e)))?;
+            StreamMessage::FriendList(
+                friends
+                    .iter()
+                    .map(|id| id.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| D::Error::custom(format!("{}", e)))?,
+            )
+        } else if let Some(event) = input.get("event").and_then(|e| e.as_str()) {
+            StreamMessage::Event(
+                StreamEvent::from_json(event, &input)
+                    .map_err(|e| D::Error::custom(format!("{}", e)))?,
+            )
         // TODO remove clone?
         } else if let Ok(tweet) = serde_json::from_value::<Tweet>(input.clone()) {
             StreamMessage::Tweet(tweet)
@@ -192,6 +216,132 @@ impl<'de> Deserialize<'de> for StreamMessage {
     }
 }
 
+/// Represents an event delivered over a user stream or the Account Activity API.
+///
+/// Every event has a `source` user who performed the action and a `target` user it was performed
+/// on, which are carried in an [`EventInfo`] alongside the object the event refers to, if any.
+/// Twitter dispatches these on the `event` field of the message, and the shape of
+/// `target_object` depends on that field: events about tweets carry the `Tweet` in question,
+/// events about lists carry the `List`, and events about users carry nothing extra.
+///
+/// [`EventInfo`]: struct.EventInfo.html
+#[derive(Debug)]
+pub enum StreamEvent {
+    /// The source user revoked the target application's access to their account.
+    AccessRevoked(EventInfo<()>),
+    /// The source user blocked the target user.
+    Block(EventInfo<()>),
+    /// The source user unblocked the target user.
+    Unblock(EventInfo<()>),
+    /// The source user liked a tweet posted by the target user.
+    Favorite(EventInfo<Tweet>),
+    /// The source user un-liked a tweet posted by the target user.
+    Unfavorite(EventInfo<Tweet>),
+    /// The source user liked a retweet made by the target user.
+    FavoritedRetweet(EventInfo<Tweet>),
+    /// The source user retweeted a retweet made by the target user.
+    RetweetedRetweet(EventInfo<Tweet>),
+    /// The source user quoted a tweet posted by the target user.
+    QuotedTweet(EventInfo<Tweet>),
+    /// The source user followed the target user.
+    Follow(EventInfo<()>),
+    /// The source user unfollowed the target user.
+    Unfollow(EventInfo<()>),
+    /// The source user created a list.
+    ListCreated(EventInfo<List>),
+    /// The source user deleted a list.
+    ListDestroyed(EventInfo<List>),
+    /// The source user updated the metadata of a list.
+    ListUpdated(EventInfo<List>),
+    /// The source user added the target user to a list.
+    ListMemberAdded(EventInfo<List>),
+    /// The source user removed the target user from a list.
+    ListMemberRemoved(EventInfo<List>),
+    /// The source user subscribed to a list owned by the target user.
+    ListUserSubscribed(EventInfo<List>),
+    /// The source user unsubscribed from a list owned by the target user.
+    ListUserUnsubscribed(EventInfo<List>),
+    /// The source user updated their profile.
+    UserUpdate(EventInfo<()>),
+    /// An event egg-mode doesn't recognize. The enclosed values are the name of the event and its
+    /// unparsed `target_object`, if it had one.
+    Other(String, EventInfo<Option<serde_json::Value>>),
+}
+
+/// The details common to every `StreamEvent`.
+///
+/// The type parameter describes the `target_object` of the event; see the documentation for each
+/// `StreamEvent` variant for what it refers to.
+#[derive(Debug)]
+pub struct EventInfo<T> {
+    /// UTC timestamp from when the event occurred.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// The user who performed the action.
+    pub source: Box<TwitterUser>,
+    /// The user the action was performed on. For events that only involve one user, this is the
+    /// same as `source`.
+    pub target: Box<TwitterUser>,
+    /// The object the action was performed on, if any.
+    pub target_object: T,
+}
+
+impl StreamEvent {
+    fn from_json(event: &str, input: &serde_json::Value) -> serde_json::Result<StreamEvent> {
+        #[derive(Deserialize)]
+        struct RawEvent<T> {
+            #[serde(with = "serde_datetime")]
+            created_at: chrono::DateTime<chrono::Utc>,
+            source: Box<TwitterUser>,
+            target: Box<TwitterUser>,
+            target_object: T,
+        }
+
+        fn info<T: DeserializeOwned>(
+            input: &serde_json::Value,
+        ) -> serde_json::Result<EventInfo<T>> {
+            let raw = RawEvent::<T>::deserialize(input)?;
+            Ok(EventInfo {
+                created_at: raw.created_at,
+                source: raw.source,
+                target: raw.target,
+                target_object: raw.target_object,
+            })
+        }
+
+        fn no_object(input: &serde_json::Value) -> serde_json::Result<EventInfo<()>> {
+            let info = info::<Option<serde_json::Value>>(input)?;
+            Ok(EventInfo {
+                created_at: info.created_at,
+                source: info.source,
+                target: info.target,
+                target_object: (),
+            })
+        }
+
+        Ok(match event {
+            "access_revoked" => StreamEvent::AccessRevoked(no_object(input)?),
+            "block" => StreamEvent::Block(no_object(input)?),
+            "unblock" => StreamEvent::Unblock(no_object(input)?),
+            "favorite" => StreamEvent::Favorite(info(input)?),
+            "unfavorite" => StreamEvent::Unfavorite(info(input)?),
+            "favorited_retweet" => StreamEvent::FavoritedRetweet(info(input)?),
+            "retweeted_retweet" => StreamEvent::RetweetedRetweet(info(input)?),
+            "quoted_tweet" => StreamEvent::QuotedTweet(info(input)?),
+            "follow" => StreamEvent::Follow(no_object(input)?),
+            "unfollow" => StreamEvent::Unfollow(no_object(input)?),
+            "list_created" => StreamEvent::ListCreated(info(input)?),
+            "list_destroyed" => StreamEvent::ListDestroyed(info(input)?),
+            "list_updated" => StreamEvent::ListUpdated(info(input)?),
+            "list_member_added" => StreamEvent::ListMemberAdded(info(input)?),
+            "list_member_removed" => StreamEvent::ListMemberRemoved(info(input)?),
+            "list_user_subscribed" => StreamEvent::ListUserSubscribed(info(input)?),
+            "list_user_unsubscribed" => StreamEvent::ListUserUnsubscribed(info(input)?),
+            "user_update" => StreamEvent::UserUpdate(no_object(input)?),
+            other => StreamEvent::Other(other.to_string(), info(input)?),
+        })
+    }
+}
+
 impl FromStr for StreamMessage {
     type Err = error::Error;
     fn from_str(input: &str) -> Result<Self, error::Error> {
@@ -205,90 +355,213 @@ impl FromStr for StreamMessage {
 }
 
 /// A `Stream` that represents a connection to the Twitter Streaming API.
+///
+/// By default, the stream ends when Twitter closes the connection, and yields an error if the
+/// connection can't be made or is cut off. To have it connect again instead, set a `Backoff` with
+/// `reconnect`.
 #[must_use = "Streams are lazy and do nothing unless polled"]
 pub struct TwitterStream {
-    buf: Vec<u8>,
+    lines: LineBuffer,
+    make_request: Option<Box<dyn Fn() -> Request<Body> + Send + Sync>>,
+    reconnect: Option<Backoff>,
+    delay: Option<Pin<Box<tokio::time::Sleep>>>,
     request: Option<Request<Body>>,
-    response: Option<ResponseFuture>,
+    response: Option<TransportFuture>,
     body: Option<Body>,
 }
 
 impl TwitterStream {
-    pub(crate) fn new(request: Request<Body>) -> TwitterStream {
+    /// Creates a stream that connects with the request given by `make_request`. The request is
+    /// built again for each reconnect, so that it gets a fresh signature.
+    pub(crate) fn new<F>(make_request: F) -> TwitterStream
+    where
+        F: Fn() -> Request<Body> + Send + Sync + 'static,
+    {
         TwitterStream {
-            buf: vec![],
+            lines: LineBuffer::default(),
+            request: Some(make_request()),
+            make_request: Some(Box::new(make_request)),
+            reconnect: None,
+            delay: None,
+            response: None,
+            body: None,
+        }
+    }
+
+    /// Creates a stream that connects with the given request. Since the request can't be sent
+    /// again, this stream can't reconnect.
+    pub(crate) fn from_request(request: Request<Body>) -> TwitterStream {
+        TwitterStream {
+            lines: LineBuffer::default(),
             request: Some(request),
+            make_request: None,
+            reconnect: None,
+            delay: None,
             response: None,
             body: None,
         }
     }
+
+    /// Sets the stream to reconnect when its connection drops, waiting between attempts according
+    /// to `backoff`.
+    ///
+    /// With this set, the stream connects again instead of yielding an error when the connection
+    /// can't be made or is cut off, when Twitter closes it, or when Twitter answers with a `420`,
+    /// `429`, or `5xx` status. Before each attempt it waits for the next delay from `backoff`, and
+    /// once a connection is made, the backoff is reset. Any other status, like `401 Unauthorized`
+    /// or a `4xx` for bad filter parameters, is still yielded as an error, since connecting again
+    /// wouldn't fix it. Messages that were cut off by the dropped connection are lost. Streams made
+    /// with `raw::response_as_stream` can't reconnect, since their request can't be sent again.
+    ///
+    /// Twitter's [guide to connecting][connecting] asks for a backoff that starts at 5 seconds and
+    /// doubles up to 320 seconds after HTTP errors, and that waits at least a minute after a `420`.
+    ///
+    /// [connecting]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/connecting
+    ///
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let token: egg_mode::Token = unimplemented!();
+    /// use std::time::Duration;
+    /// use egg_mode::Backoff;
+    ///
+    /// let backoff = Backoff::exponential(Duration::from_secs(5), 2, Duration::from_secs(320));
+    /// let stream = egg_mode::stream::sample(&token).reconnect(backoff);
+    /// # }
+    /// ```
+    pub fn reconnect(self, backoff: Backoff) -> TwitterStream {
+        TwitterStream {
+            reconnect: Some(backoff),
+            ..self
+        }
+    }
+
+    /// If this stream reconnects, drops the current connection and schedules the next attempt.
+    /// Returns `false` if the stream doesn't reconnect.
+    fn schedule_reconnect(&mut self) -> bool {
+        match self.reconnect {
+            Some(ref mut backoff) if self.make_request.is_some() => {
+                let delay = backoff.next_delay();
+                self.delay = Some(Box::pin(tokio::time::sleep(delay)));
+                self.lines = LineBuffer::default();
+                self.response = None;
+                self.body = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns whether connecting again could fix a stream that got the given status.
+fn can_reconnect(status: hyper::StatusCode) -> bool {
+    status.as_u16() == 420
+        || status == hyper::StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
 }
 
 impl Stream for TwitterStream {
     type Item = Result<StreamMessage, error::Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        if let Some(req) = self.request.take() {
-            self.response = Some(get_response(req));
-        }
-
-        if let Some(mut resp) = self.response.take() {
-            match Pin::new(&mut resp).poll(cx) {
-                Poll::Pending => {
-                    self.response = Some(resp);
+        'connect: loop {
+            if let Some(mut delay) = self.delay.take() {
+                if delay.as_mut().poll(cx).is_pending() {
+                    self.delay = Some(delay);
                     return Poll::Pending;
                 }
-                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
-                Poll::Ready(Ok(resp)) => {
-                    let status = resp.status();
-                    if !status.is_success() {
-                        //TODO: should i try to pull the response regardless?
-                        return Poll::Ready(Some(Err(error::Error::BadStatus(status))));
-                    }
+                self.request = self
+                    .make_request
+                    .as_ref()
+                    .map(|make_request| make_request());
+            }
 
-                    self.body = Some(resp.into_body());
-                }
+            if let Some(req) = self.request.take() {
+                self.response = Some(get_response(req));
             }
-        }
 
-        if let Some(mut body) = self.body.take() {
-            loop {
-                match Pin::new(&mut body).poll_next(cx) {
+            if let Some(mut resp) = self.response.take() {
+                match Pin::new(&mut resp).poll(cx) {
                     Poll::Pending => {
-                        self.body = Some(body);
+                        self.response = Some(resp);
                         return Poll::Pending;
                     }
-                    Poll::Ready(None) => {
-                        return Poll::Ready(None);
+                    Poll::Ready(Err(e)) => {
+                        if self.schedule_reconnect() {
+                            continue 'connect;
+                        }
+                        return Poll::Ready(Some(Err(e.into())));
                     }
-                    Poll::Ready(Some(Err(e))) => {
+                    Poll::Ready(Ok(resp)) => {
+                        let status = resp.status();
+                        if status == hyper::StatusCode::UNAUTHORIZED {
+                            return Poll::Ready(Some(Err(error::Error::Unauthorized {
+                                code: -1,
+                                message: status.to_string(),
+                            })));
+                        }
+                        if !status.is_success() {
+                            if can_reconnect(status) && self.schedule_reconnect() {
+                                continue 'connect;
+                            }
+                            //TODO: should i try to pull the response regardless?
+                            return Poll::Ready(Some(Err(error::Error::BadStatus(
+                                status,
+                                String::new(),
+                            ))));
+                        }
+
+                        if let Some(ref mut backoff) = self.reconnect {
+                            backoff.reset();
+                        }
+                        self.body = Some(resp.into_body());
+                    }
+                }
+            }
+
+            if let Some(mut body) = self.body.take() {
+                loop {
+                    if let Some(line) = self.lines.next_line() {
                         self.body = Some(body);
-                        return Poll::Ready(Some(Err(e.into())));
+                        let resp = if let Ok(msg_str) = std::str::from_utf8(&line) {
+                            StreamMessage::from_str(msg_str)
+                        } else {
+                            Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "stream did not contain valid UTF-8",
+                            )
+                            .into())
+                        };
+
+                        return Poll::Ready(Some(resp));
                     }
-                    Poll::Ready(Some(Ok(chunk))) => {
-                        self.buf.extend(&*chunk);
 
-                        if let Some(pos) = self.buf.windows(2).position(|w| w == b"\r\n") {
+                    match Pin::new(&mut body).poll_next(cx) {
+                        Poll::Pending => {
+                            self.body = Some(body);
+                            return Poll::Pending;
+                        }
+                        Poll::Ready(None) => {
+                            if self.schedule_reconnect() {
+                                continue 'connect;
+                            }
+                            return Poll::Ready(None);
+                        }
+                        Poll::Ready(Some(Err(e))) => {
+                            if self.schedule_reconnect() {
+                                continue 'connect;
+                            }
                             self.body = Some(body);
-                            let pos = pos + 2;
-                            let resp = if let Ok(msg_str) = std::str::from_utf8(&self.buf[..pos]) {
-                                StreamMessage::from_str(msg_str)
-                            } else {
-                                Err(io::Error::new(
-                                    io::ErrorKind::InvalidData,
-                                    "stream did not contain valid UTF-8",
-                                )
-                                .into())
-                            };
-
-                            self.buf.drain(..pos);
-                            return Poll::Ready(Some(Ok(resp?)));
+                            return Poll::Ready(Some(Err(e.into())));
+                        }
+                        Poll::Ready(Some(Ok(chunk))) => {
+                            self.lines.push(&chunk);
                         }
                     }
                 }
+            } else {
+                return Poll::Ready(Some(Err(error::Error::FutureAlreadyCompleted)));
             }
-        } else {
-            Poll::Ready(Some(Err(error::Error::FutureAlreadyCompleted)))
         }
     }
 }
@@ -468,9 +741,9 @@ impl StreamBuilder {
             params.add_param_ref("locations", locs);
         }
 
-        let req = post(self.url, token, Some(&params));
-
-        TwitterStream::new(req)
+        let url = self.url;
+        let token = token.clone();
+        TwitterStream::new(move || post(url, &token, Some(&params)))
     }
 }
 
@@ -489,8 +762,8 @@ pub fn filter() -> StreamBuilder {
 /// [`StreamBuilder`]: struct.StreamBuilder.html
 /// [`filter`]: fn.filter.html
 pub fn sample(token: &Token) -> TwitterStream {
-    let req = get(links::stream::SAMPLE, token, None);
-    TwitterStream::new(req)
+    let token = token.clone();
+    TwitterStream::new(move || get(links::stream::SAMPLE, &token, None))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -545,6 +818,153 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_friends() {
+        let msg = StreamMessage::from_str(r#"{"friends_str":["1234","5678"]}"#).unwrap();
+        if let StreamMessage::FriendList(friends) = msg {
+            assert_eq!(friends, vec![1234, 5678]);
+        } else {
+            panic!("Not a friend list")
+        }
+    }
+
+    #[test]
+    fn parse_events() {
+        let users: Vec<serde_json::Value> =
+            serde_json::from_str(&load_file("sample_payloads/user_array.json")).unwrap();
+        let tweet: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-stream.json")).unwrap();
+
+        let favorite = serde_json::json!({
+            "event": "favorite",
+            "created_at": "Sat Sep 04 16:10:54 +0000 2010",
+            "source": users[0],
+            "target": users[1],
+            "target_object": tweet,
+        });
+        let msg: StreamMessage = serde_json::from_value(favorite).unwrap();
+        if let StreamMessage::Event(StreamEvent::Favorite(info)) = msg {
+            assert_eq!(info.source.id, users[0]["id"].as_u64().unwrap());
+            assert_eq!(info.target.id, users[1]["id"].as_u64().unwrap());
+            assert_eq!(info.target_object.id, tweet["id"].as_u64().unwrap());
+        } else {
+            panic!("Not a favorite event")
+        }
+
+        let follow = serde_json::json!({
+            "event": "follow",
+            "created_at": "Sat Sep 04 16:10:54 +0000 2010",
+            "source": users[0],
+            "target": users[1],
+        });
+        let msg: StreamMessage = serde_json::from_value(follow).unwrap();
+        if let StreamMessage::Event(StreamEvent::Follow(_)) = msg {
+            // OK
+        } else {
+            panic!("Not a follow event")
+        }
+    }
+
+    #[tokio::test]
+    async fn read_chunked_stream() {
+        use crate::common::tests::serve;
+        use futures::TryStreamExt;
+
+        let tweet: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-stream.json")).unwrap();
+        let line = format!("{}\r\n", tweet);
+        let (first_half, second_half) = line.split_at(line.len() / 2);
+
+        // the first tweet arrives split across two chunks, followed by a keep-alive, then the
+        // second tweet with the end of the body
+        let chunks = [first_half, second_half, "\r\n", &line];
+        let mut response = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_string();
+        for chunk in chunks.iter() {
+            response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+        }
+        response.push_str("0\r\n\r\n");
+        let (addr, _) = serve(response.into_bytes());
+
+        let url = format!("http://{}/", addr);
+        let messages =
+            TwitterStream::new(move || Request::get(url.as_str()).body(Body::empty()).unwrap())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+
+        let tweets = messages
+            .iter()
+            .filter(|msg| matches!(msg, StreamMessage::Tweet(_)))
+            .count();
+        assert_eq!(tweets, 2);
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[1], StreamMessage::Ping));
+    }
+
+    #[tokio::test]
+    async fn reconnect_with_backoff() {
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use futures::StreamExt;
+        use hyper::StatusCode;
+        use std::time::Duration;
+
+        let tweet: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-stream.json")).unwrap();
+        let line = format!("{}\r\n", tweet);
+        let token = Token::Bearer("abc".to_string());
+        let backoff = || Backoff::linear(Duration::from_millis(1), Duration::from_millis(1));
+
+        // Twitter is unavailable at first, then each connection sends one tweet and closes
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Headers::new(),
+                "over capacity",
+            )
+            .respond(StatusCode::OK, Headers::new(), line.clone());
+        let messages = with_transport(
+            mock.clone(),
+            sample(&token)
+                .reconnect(backoff())
+                .take(2)
+                .collect::<Vec<_>>(),
+        )
+        .await;
+        assert_eq!(messages.len(), 2);
+        for msg in messages {
+            assert!(matches!(msg, Ok(StreamMessage::Tweet(_))));
+        }
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|req| req.uri.path() == "/1.1/statuses/sample.json"));
+
+        // without a backoff, the first error ends things
+        let mock = MockTransport::new().respond(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Headers::new(),
+            "over capacity",
+        );
+        let first = with_transport(mock.clone(), sample(&token).next()).await;
+        assert!(matches!(
+            first,
+            Some(Err(error::Error::BadStatus(
+                StatusCode::SERVICE_UNAVAILABLE,
+                _
+            )))
+        ));
+
+        // statuses that reconnecting can't fix are still errors
+        let mock = MockTransport::new().respond(StatusCode::NOT_ACCEPTABLE, Headers::new(), "");
+        let first = with_transport(mock.clone(), sample(&token).reconnect(backoff()).next()).await;
+        assert!(matches!(
+            first,
+            Some(Err(error::Error::BadStatus(StatusCode::NOT_ACCEPTABLE, _)))
+        ));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
     #[test]
     fn parse_empty_stream() {
         let msg = StreamMessage::from_str("").unwrap();
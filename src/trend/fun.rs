@@ -1,8 +1,10 @@
 use crate::common::*;
-use crate::error::Result;
-use crate::trend::TrendLocation;
+use crate::error::{Error::InvalidResponse, Result};
+use crate::trend::{Trend, TrendLocation};
 use crate::{auth, links};
 
+use super::raw::RawTrendPlace;
+
 ///Returns the locations that Twitter has trending topic information for, closest to a
 ///specified location.
 pub async fn closest(
@@ -24,3 +26,36 @@ pub async fn available(token: &auth::Token) -> Result<Response<Vec<TrendLocation
     let req = get(links::trend::AVAILABLE, token, None);
     request_with_json_response(req).await
 }
+
+///Returns the topics currently trending in the location with the given WOEID ("where on earth
+///identifier").
+///
+///The WOEIDs that Twitter has trends for can be found with `available` or `closest`. A WOEID of
+///`1` gets worldwide trends. If `exclude_hashtags` is `true`, trending hashtags are left out.
+pub async fn place(
+    woeid: u32,
+    exclude_hashtags: bool,
+    token: &auth::Token,
+) -> Result<Response<Vec<Trend>>> {
+    let mut params = ParamList::new().add_param("id", woeid.to_string());
+    if exclude_hashtags {
+        params.add_param_ref("exclude", "hashtags");
+    }
+
+    let req = get(links::trend::PLACE, token, Some(&params));
+
+    let resp = request_with_json_response::<Vec<RawTrendPlace>>(req).await?;
+    Response::try_map(resp, unwrap_place)
+}
+
+///Takes the trends out of the single-element array that `trends/place` wraps them in.
+pub(crate) fn unwrap_place(places: Vec<RawTrendPlace>) -> Result<Vec<Trend>> {
+    places
+        .into_iter()
+        .next()
+        .map(|place| place.trends)
+        .ok_or(InvalidResponse(
+            "trends/place returned an empty array",
+            None,
+        ))
+}
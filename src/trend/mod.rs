@@ -1,11 +1,13 @@
 //! Sturcts and functions for working with trending topic in Twitter.
 //!
-//! In this module, you are able to get locations with trending topics.
+//! In this module, you are able to get locations with trending topics, and the topics trending
+//! in them.
 //!
 //! ## Types
 //! - `TrendLocation`: the element of trending information returned by trend API
 //! - `PlaceType`: a member in `TrendLocation`, which includes the code and related name
 //!   to specify the kind of place
+//! - `Trend`: a single trending topic, returned by `place`
 use serde::{Deserialize, Serialize};
 
 mod fun;
@@ -58,3 +60,40 @@ pub struct PlaceType {
     ///The name of the location type
     pub name: String,
 }
+
+///A topic that's trending in some location, as returned by `place`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Trend {
+    ///The name of the topic, like a hashtag or phrase.
+    pub name: String,
+    ///A link to search for the topic on twitter.com.
+    pub url: String,
+    ///The topic as a URL-encoded search query, ready to pass to the search API.
+    pub query: String,
+    ///How many tweets about the topic were posted in the last 24 hours, if Twitter has that
+    ///information.
+    pub tweet_volume: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::raw::RawTrendPlace;
+    use super::unwrap_place;
+    use crate::common::tests::load_file;
+
+    #[test]
+    fn parse_trends_place() {
+        let sample = load_file("sample_payloads/trends_place.json");
+        let places: Vec<RawTrendPlace> = serde_json::from_str(&sample).unwrap();
+        let trends = unwrap_place(places).unwrap();
+
+        assert_eq!(trends.len(), 3);
+        assert_eq!(trends[0].name, "#GiftAGamer");
+        assert_eq!(trends[0].query, "%23GiftAGamer");
+        assert_eq!(trends[0].tweet_volume, None);
+        assert_eq!(trends[1].tweet_volume, Some(12345));
+        assert_eq!(trends[2].url, "http://twitter.com/search?q=Rust");
+
+        assert!(unwrap_place(Vec::new()).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use super::PlaceType;
+use super::{PlaceType, Trend};
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,3 +13,9 @@ pub struct RawTrendLocation {
     pub url: String,
     pub woeid: u32,
 }
+
+///The single object in the array returned by `trends/place`, wrapping the list of trends.
+#[derive(Debug, Deserialize)]
+pub struct RawTrendPlace {
+    pub trends: Vec<Trend>,
+}
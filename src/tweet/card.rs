@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use chrono;
+use serde::{Deserialize, Serialize};
+
+///A "card" attached to a tweet, as shown by Twitter's own clients.
+///
+///Cards are how Twitter attaches rich content to tweets, such as link previews and native polls.
+///They aren't part of the documented API, so their contents are loosely typed: each card has a
+///`name` describing what kind of card it is, and a set of `binding_values` holding its data. For
+///polls, use `Tweet::poll` instead of reading these directly.
+///
+///Cards are only included when loading tweets with `show`, `lookup`, or `lookup_map`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Card {
+    ///The kind of card this is, for example `poll2choice_text_only` or `summary_large_image`.
+    pub name: String,
+    ///The URL the card was created for, if any.
+    pub url: Option<String>,
+    ///The data making up the card, keyed by name.
+    #[serde(default)]
+    pub binding_values: HashMap<String, CardValue>,
+}
+
+///A single value in a `Card`'s `binding_values`.
+///
+///Which of the fields is set depends on `kind`. For example, a value with a `kind` of `STRING`
+///has its data in `string_value`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CardValue {
+    ///The type of this value, like `STRING`, `BOOLEAN`, or `IMAGE`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    ///The value itself, if this is a `STRING` value.
+    pub string_value: Option<String>,
+    ///The value itself, if this is a `BOOLEAN` value.
+    pub boolean_value: Option<bool>,
+}
+
+///A native poll attached to a tweet.
+///
+///This is returned by `Tweet::poll`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poll {
+    ///The choices in the poll along with how many votes each has received, in the order they
+    ///appear in the tweet.
+    pub options: Vec<(String, u64)>,
+    ///When the poll closes, or closed.
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+    ///Whether the poll has closed and the vote counts won't change anymore.
+    pub counts_are_final: bool,
+}
+
+impl Card {
+    ///If this card is a native poll, parses it into a `Poll`.
+    ///
+    ///Returns `None` if this card isn't a poll, or if it's missing any of the fields a poll needs.
+    pub fn poll(&self) -> Option<Poll> {
+        if !self.name.starts_with("poll") || !self.name.contains("choice") {
+            return None;
+        }
+
+        let mut options = Vec::new();
+        for idx in 1.. {
+            let label = match self.string(&format!("choice{}_label", idx)) {
+                Some(label) => label,
+                None => break,
+            };
+            let count = self
+                .string(&format!("choice{}_count", idx))
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(0);
+            options.push((label.to_string(), count));
+        }
+
+        if options.is_empty() {
+            return None;
+        }
+
+        let ends_at = self
+            .string("end_datetime_utc")?
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .ok()?;
+        let counts_are_final = self
+            .binding_values
+            .get("counts_are_final")
+            .and_then(|val| val.boolean_value)
+            .unwrap_or(false);
+
+        Some(Poll {
+            options,
+            ends_at,
+            counts_are_final,
+        })
+    }
+
+    fn string(&self, key: &str) -> Option<&str> {
+        self.binding_values.get(key)?.string_value.as_deref()
+    }
+}
@@ -4,10 +4,12 @@
 
 use std::collections::HashMap;
 
+use futures::stream::{self, StreamExt, TryStreamExt};
+
 use crate::common::*;
 use crate::error::{Error::InvalidResponse, Result};
 use crate::user::UserID;
-use crate::{auth, cursor, links};
+use crate::{auth, cursor, error, links, search, user};
 use serde_json;
 
 use super::*;
@@ -18,7 +20,9 @@ pub async fn show(id: u64, token: &auth::Token) -> Result<Response<Tweet>> {
         .extended_tweets()
         .add_param("id", id.to_string())
         .add_param("include_my_retweet", "true")
-        .add_param("include_ext_alt_text", "true");
+        .add_param("include_ext_alt_text", "true")
+        .add_param("include_cards", "true")
+        .add_param("cards_platform", "Web-12");
     let req = get(links::statuses::SHOW, token, Some(&params));
     request_with_json_response(req).await
 }
@@ -51,7 +55,13 @@ pub async fn retweets_of(id: u64, count: u32, token: &auth::Token) -> Result<Res
 ///error from Twitter.
 pub fn retweeters_of(id: u64, token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
     let params = ParamList::new().add_param("id", id.to_string());
-    cursor::CursorIter::new(links::statuses::RETWEETERS_OF, token, Some(params), None)
+    cursor::CursorIter::new(
+        links::statuses::RETWEETERS_OF,
+        token,
+        Some(params),
+        None,
+        None,
+    )
 }
 
 ///Lookup tweet information for the given list of tweet IDs.
@@ -72,7 +82,9 @@ pub async fn lookup<I: IntoIterator<Item = u64>>(
     let params = ParamList::new()
         .extended_tweets()
         .add_param("id", id_param)
-        .add_param("include_ext_alt_text", "true");
+        .add_param("include_ext_alt_text", "true")
+        .add_param("include_cards", "true")
+        .add_param("cards_platform", "Web-12");
 
     let req = post(links::statuses::LOOKUP, token, Some(&params));
     request_with_json_response(req).await
@@ -100,23 +112,24 @@ pub async fn lookup_map<I: IntoIterator<Item = u64>>(
         .extended_tweets()
         .add_param("id", id_param)
         .add_param("map", "true")
-        .add_param("include_ext_alt_text", "true");
+        .add_param("include_ext_alt_text", "true")
+        .add_param("include_cards", "true")
+        .add_param("cards_platform", "Web-12");
 
     let req = post(links::statuses::LOOKUP, token, Some(&params));
     let parsed = request_with_json_response::<serde_json::Value>(req).await?;
+
+    Response::try_map(parsed, |resp| parse_lookup_map(&resp))
+}
+
+///Parses the `{"id": {"<id>": tweet-or-null, ...}}` object returned by `statuses/lookup` when
+///`map=true` is given.
+pub(crate) fn parse_lookup_map(resp: &serde_json::Value) -> Result<HashMap<u64, Option<Tweet>>> {
     let mut map = HashMap::new();
 
-    for (key, val) in parsed
-        .response
-        .get("id")
-        .and_then(|v| v.as_object())
-        .ok_or_else(|| {
-            InvalidResponse(
-                "unexpected response for lookup_map",
-                Some(parsed.response.to_string()),
-            )
-        })?
-    {
+    for (key, val) in resp.get("id").and_then(|v| v.as_object()).ok_or_else(|| {
+        InvalidResponse("unexpected response for lookup_map", Some(resp.to_string()))
+    })? {
         let id = key
             .parse::<u64>()
             .map_err(|_| InvalidResponse("could not parse id as integer", Some(key.to_string())))?;
@@ -128,7 +141,77 @@ pub async fn lookup_map<I: IntoIterator<Item = u64>>(
         }
     }
 
-    Ok(Response::map(parsed, |_| map))
+    Ok(map)
+}
+
+///Load the given tweet along with every reply beneath it that Twitter's search can find.
+///
+///Twitter doesn't have an endpoint to load the replies to a tweet, so this function uses the
+///common workaround of searching for tweets sent `to:` the tweet's author since the tweet was
+///posted, and keeping the ones whose reply chain leads back to it. This means it inherits the
+///limits of the standard search API:
+///
+///- Search only covers roughly the last 7 days, so replies to older tweets will be missing.
+///- Search is not exhaustive, and may leave out some replies even within that window.
+///- Only replies that are addressed to the original author are searched for. A reply to a reply
+///  that doesn't also mention the original author can't be found, and neither can anything beneath
+///  it.
+///
+///The returned `Vec` starts with the requested tweet, and then lists its replies in thread order:
+///each reply is immediately followed by its own replies, and replies to the same tweet are
+///ordered oldest-first. The rate-limit information is from the last search call that was made.
+pub async fn thread(root_id: u64, token: &auth::Token) -> Result<Response<Vec<Tweet>>> {
+    let root = show(root_id, token).await?;
+    let author = match root.user {
+        Some(ref user) => user.screen_name.clone(),
+        None => return Err(error::Error::MissingValue("user")),
+    };
+
+    let mut page = search::search(format!("to:{}", author))
+        .since_tweet(root_id)
+        .count(100)
+        .call(token)
+        .await?;
+    let mut replies = Vec::new();
+
+    // `older` keeps the `since_id` from the original search, so every page only holds tweets
+    // newer than the root, and the search runs out of pages once it reaches it
+    while page.has_older() {
+        let older = page.older(token).await?;
+        replies.append(&mut page.statuses);
+        page = older;
+    }
+    replies.append(&mut page.statuses);
+
+    Ok(Response::map(page, |_| {
+        thread_order(root.response, replies)
+    }))
+}
+
+///Arranges the given replies into thread order beneath `root`, dropping any that don't lead back
+///to it.
+pub(crate) fn thread_order(root: Tweet, replies: Vec<Tweet>) -> Vec<Tweet> {
+    let mut children: HashMap<u64, Vec<Tweet>> = HashMap::new();
+    for reply in replies {
+        if let Some(parent) = reply.in_reply_to_status_id {
+            children.entry(parent).or_default().push(reply);
+        }
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|t| std::cmp::Reverse(t.id));
+    }
+
+    let mut thread = Vec::new();
+    let mut stack = vec![root];
+    while let Some(tweet) = stack.pop() {
+        // siblings are sorted newest-first so the oldest one is popped first
+        if let Some(replies) = children.remove(&tweet.id) {
+            stack.extend(replies);
+        }
+        thread.push(tweet);
+    }
+
+    thread
 }
 
 ///Make a `Timeline` struct for navigating the collection of tweets posted by the authenticated
@@ -163,6 +246,22 @@ pub fn mentions_timeline(token: &auth::Token) -> Timeline {
 ///retweets.
 ///
 ///Twitter will only load the most recent 3,200 tweets with this method.
+///
+///The account can be given as anything that converts into a `UserID`, and the resulting
+///`Timeline` pages the same way either way:
+///
+///```rust,no_run
+///# use egg_mode::Token;
+///# #[tokio::main]
+///# async fn main() {
+///# let token: Token = unimplemented!();
+///// only the tweets @rustlang posted themselves: no replies, no retweets
+///let by_name = egg_mode::tweet::user_timeline("rustlang", false, false, &token);
+///let by_id = egg_mode::tweet::user_timeline(165262228, false, false, &token);
+///
+///let (by_name, page) = by_name.start().await.unwrap();
+///# }
+///```
 pub fn user_timeline<T: Into<UserID>>(
     acct: T,
     with_replies: bool,
@@ -234,12 +333,31 @@ pub async fn like(id: u64, token: &auth::Token) -> Result<Response<Tweet>> {
 ///Clears a like of the given status as the authenticated user.
 ///
 ///On success, the future returned by this function yields the given tweet.
+///
+///Clearing a like that isn't there is treated as a success: Twitter answers with error 144 ("No
+///status found with that ID") when the tweet isn't liked, so in that case this loads the tweet with
+///`show` and returns it instead. If the tweet really doesn't exist, the error from `show` is
+///returned.
 pub async fn unlike(id: u64, token: &auth::Token) -> Result<Response<Tweet>> {
     let params = ParamList::new()
         .extended_tweets()
         .add_param("id", id.to_string());
     let req = post(links::statuses::UNLIKE, token, Some(&params));
-    request_with_json_response(req).await
+    match request_with_json_response(req).await {
+        Err(ref e) if is_not_liked(e) => show(id, token).await,
+        res => res,
+    }
+}
+
+///Twitter's error code for `unlike` when the given tweet isn't liked, or doesn't exist.
+const NOT_LIKED_CODE: i32 = 144;
+
+///Returns whether the given error is Twitter's answer to clearing a like that isn't there.
+pub(crate) fn is_not_liked(err: &error::Error) -> bool {
+    match err {
+        error::Error::TwitterError(_, errs) => errs.errors.iter().any(|e| e.code == NOT_LIKED_CODE),
+        _ => false,
+    }
 }
 
 ///Delete the given tweet. The authenticated user must be the user who posted the given tweet.
@@ -251,3 +369,178 @@ pub async fn delete(id: u64, token: &auth::Token) -> Result<Response<Tweet>> {
     let req = post(&url, token, Some(&params));
     request_with_json_response(req).await
 }
+
+///The most characters Twitter allows in a tweet.
+const MAX_TWEET_CHARS: usize = 280;
+
+///Post the given texts as a thread, with each tweet replying to the one before it.
+///
+///Each text is posted in order as the authenticated user. The first tweet is posted on its own,
+///and every tweet after it is posted as a reply to the previous one, with
+///`auto_populate_reply_metadata` set so the thread reads the same way as one made on
+///twitter.com. On success, the returned tweets are in thread order, and the rate-limit
+///information is from the last one posted.
+///
+///Before anything is posted, the length of every text is checked, and if one is longer than 280
+///characters, an error of `TweetTooLong` is returned without posting anything. Note that this
+///counts characters directly, while Twitter counts links as 23 characters and some scripts as two
+///characters per letter, so Twitter may still reject a text that passes this check.
+///
+///If a tweet fails to post partway through, the thread stops there, and the returned
+///`PostThreadError` holds the tweets that were already posted along with the error.
+///
+///```rust,no_run
+///# use egg_mode::Token;
+///# #[tokio::main]
+///# async fn main() {
+///# let token: Token = unimplemented!();
+///let thread = egg_mode::tweet::post_thread(
+///    vec!["i have some thoughts (1/2)", "that's all (2/2)"],
+///    &token,
+///)
+///.await
+///.unwrap();
+///# }
+///```
+pub async fn post_thread<I, S>(
+    texts: I,
+    token: &auth::Token,
+) -> std::result::Result<Response<Vec<Tweet>>, PostThreadError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<Cow<'static, str>>,
+{
+    let texts = texts.into_iter().map(Into::into).collect::<Vec<_>>();
+    let mut posted = Vec::with_capacity(texts.len());
+    let mut rate_limit_status = RateLimit {
+        limit: -1,
+        remaining: -1,
+        reset: -1,
+    };
+
+    for (index, text) in texts.iter().enumerate() {
+        let length = text.chars().count();
+        if length > MAX_TWEET_CHARS {
+            return Err(PostThreadError {
+                posted,
+                error: error::Error::TweetTooLong { index, length },
+            });
+        }
+    }
+
+    for text in texts {
+        let draft = match posted.last() {
+            Some(previous) => DraftTweet::new(text)
+                .in_reply_to(previous.id)
+                .auto_populate_reply_metadata(true),
+            None => DraftTweet::new(text),
+        };
+
+        match draft.send(token).await {
+            Ok(resp) => {
+                rate_limit_status = resp.rate_limit_status;
+                posted.push(resp.response);
+            }
+            Err(error) => return Err(PostThreadError { posted, error }),
+        }
+    }
+
+    Ok(Response::new(rate_limit_status, posted))
+}
+
+///Load the full profiles of the users who posted the given tweets, for tweets where Twitter only
+///sent the user's ID.
+///
+///Tweets loaded with `trim_user` set, and some tweets from the streaming API, only carry the ID of
+///the user who posted them in `author_id`, leaving `user` empty. This collects the distinct IDs
+///of those users, including the authors of any retweeted or quoted tweets, and loads all of them
+///with `user::lookup`, making one call per 100 users rather than one call per tweet. Tweets that
+///already have their `user` are left alone, and if no author is missing, no network call is made.
+///
+///If an author can't be loaded, for example because their account was suspended, their tweets
+///keep an empty `user`.
+pub async fn hydrate_authors(tweets: &mut [Tweet], token: &auth::Token) -> Result<()> {
+    let mut ids = Vec::new();
+    for tweet in tweets.iter() {
+        tweet.trimmed_authors(&mut ids);
+    }
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut users = HashMap::with_capacity(ids.len());
+    for chunk in ids.chunks(100) {
+        let resp = user::lookup(chunk.iter().copied(), token).await?;
+        users.extend(resp.response.into_iter().map(|user| (user.id, user)));
+    }
+
+    for tweet in tweets.iter_mut() {
+        tweet.fill_authors(&users);
+    }
+
+    Ok(())
+}
+
+///The most `oembed` requests that `oembed_many` will have in flight at once.
+const OEMBED_CONCURRENCY: usize = 8;
+
+///Load the embed-ready HTML for the given tweet.
+///
+///This can be called with a Bearer token, since the embed only shows what's publicly visible on
+///the tweet. Twitter doesn't send rate-limit information for this call, so the `RateLimit` in the
+///returned `Response` will have all its fields set to -1.
+pub async fn oembed(
+    id: u64,
+    options: &OEmbedOptions,
+    token: &auth::Token,
+) -> Result<Response<OEmbed>> {
+    let params = options.params(format!("https://twitter.com/i/status/{}", id));
+    let req = get(links::statuses::OEMBED, token, Some(&params));
+    request_with_json_response(req).await
+}
+
+///Load the embed-ready HTML for several tweets at once.
+///
+///This calls `oembed` for each of the given IDs, running up to eight of those calls at a time,
+///which is much faster than loading them one after another when rendering a page with many
+///tweets. The returned embeds are in the same order as the given IDs. If any of the calls fails,
+///the first error is returned and the embeds that were already loaded are dropped.
+///
+///Since `oembed` can be called with a Bearer token, this is a good place to use one: app-only
+///authentication isn't limited by any one user's requests.
+///
+///```rust,no_run
+///# use egg_mode::Token;
+///# #[tokio::main]
+///# async fn main() {
+///# let token: Token = unimplemented!();
+///use egg_mode::tweet::{self, OEmbedOptions};
+///
+///let options = OEmbedOptions::new().omit_script(true);
+///let embeds = tweet::oembed_many(vec![1261253754969640960, 1261252734587174913], &options, &token)
+///    .await
+///    .unwrap();
+///for embed in &embeds {
+///    println!("{}", embed.html);
+///}
+///# }
+///```
+pub async fn oembed_many<I: IntoIterator<Item = u64>>(
+    ids: I,
+    options: &OEmbedOptions,
+    token: &auth::Token,
+) -> Result<Vec<Response<OEmbed>>> {
+    stream::iter(ids)
+        .map(|id| oembed(id, options, token))
+        .buffered(OEMBED_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+///Parse a single tweet from the given JSON text, as Twitter sends it.
+///
+///This is meant for tweets that arrive without going through egg-mode, like the payloads the
+///Account Activity API posts to a webhook, or JSON saved from an earlier call. Tweets serialized
+///by egg-mode can be read back with this as well.
+pub fn from_json_str(json: &str) -> Result<Tweet> {
+    Ok(serde_json::from_str(json)?)
+}
@@ -21,6 +21,9 @@
 //!   coordinate are available.
 //! - `Timeline`: Returned by several functions in this module, this is how you cursor through a
 //!   collection of tweets. See the struct-level documentation for details.
+//! - `SeenSet`: A record of tweet IDs that have already been handled, to skip repeated tweets
+//!   when loading the same timeline or search several times. `dedup_tweets` applies one to a
+//!   `Stream` of tweets.
 //!
 //! ## Functions
 //!
@@ -30,6 +33,7 @@
 //! authenticated user's account.
 //!
 //! - `delete` (for creating a tweet, see `DraftTweet`)
+//! - `post_thread` (for posting a single tweet, see `DraftTweet`)
 //! - `like`/`unlike`
 //! - `retweet`/`unretweet`
 //!
@@ -42,6 +46,7 @@
 //! - `lookup`/`lookup_map` (for the differences between these functions, see their respective
 //!   documentations.)
 //! - `retweeters_of`
+//! - `thread`
 //! - `retweets_of`
 //!
 //! ### `Timeline` cursors
@@ -52,13 +57,15 @@
 //!
 //! - `home_timeline`/`mentions_timeline`/`retweets_of_me`
 //! - `user_timeline`/`liked_by`
+//!
+//! ### Parsing
+//!
+//! - `from_json_str`: For tweets that were received outside of egg-mode, like through a webhook.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::future::Future;
-use std::pin::Pin;
 use std::str::FromStr;
-use std::task::{Context, Poll};
 
 use chrono;
 use hyper::{Body, Request};
@@ -70,10 +77,16 @@ use crate::error::{Error::InvalidResponse, Result};
 use crate::stream::FilterLevel;
 use crate::{auth, entities, error, links, media, place, user};
 
+mod card;
 mod fun;
+mod oembed;
 mod raw;
+mod seen;
 
+pub use self::card::*;
 pub use self::fun::*;
+pub use self::oembed::*;
+pub use self::seen::*;
 
 round_trip! { raw::RawTweet,
     ///Represents a single status update.
@@ -88,7 +101,7 @@ round_trip! { raw::RawTweet,
     ///* `text`
     ///* `id`
     ///* `created_at`
-    ///* `user`
+    ///* `user`/`author_id`
     ///* `source`
     ///* `favorite_count`/`retweet_count`
     ///* `lang`, though third-party clients usually don't surface this at a user-interface level.
@@ -147,9 +160,20 @@ round_trip! { raw::RawTweet,
     ///* `withheld_scope`
     #[derive(Debug, Clone)]
     pub struct Tweet {
-        //If the user has contributors enabled, this will show which accounts contributed to this
-        //tweet.
-        //pub contributors: Option<Contributors>,
+        ///The numeric ID of the user who posted this tweet.
+        ///
+        ///This is set whenever `user` is, and also when Twitter only sent the user's ID instead of
+        ///their full profile, as it does when tweets are loaded with `trim_user` set. In that case,
+        ///`user` is `None` until it's filled in with `hydrate_author` or `hydrate_authors`.
+        pub author_id: Option<u64>,
+        ///If present, the card attached to this tweet, such as a link preview or a poll.
+        ///
+        ///Cards are only loaded by `show`, `lookup`, and `lookup_map`. To read a poll from the card,
+        ///use `poll`.
+        pub card: Option<Card>,
+        ///If the user has contributors enabled, this will show which accounts contributed to this
+        ///tweet.
+        pub contributors: Option<Vec<Contributor>>,
         ///If present, the location coordinate attached to the tweet, as a (latitude, longitude) pair.
         pub coordinates: Option<(f64, f64)>,
         ///UTC timestamp from when the tweet was posted.
@@ -197,9 +221,23 @@ round_trip! { raw::RawTweet,
         pub quoted_status_id: Option<u64>,
         ///If this tweet is quoting another by link, contains the quoted tweet.
         pub quoted_status: Option<Box<Tweet>>,
-        //"A set of key-value pairs indicating the intended contextual delivery of the containing
-        //Tweet. Currently used by Twitter’s Promoted Products."
-        //pub scopes: Option<Scopes>,
+        ///The number of times this tweet has been quoted, if Twitter included it.
+        ///
+        ///This is only included in some responses, like the ones from the streaming and
+        ///premium/enterprise APIs. `None` means the count wasn't given, not that the tweet hasn't
+        ///been quoted.
+        pub quote_count: Option<u64>,
+        ///The number of times this tweet has been replied to, if Twitter included it.
+        ///
+        ///Like `quote_count`, this is only included in some responses, so `None` means the count
+        ///wasn't given, not that the tweet has no replies.
+        pub reply_count: Option<u64>,
+        ///"A set of key-value pairs indicating the intended contextual delivery of the containing
+        ///Tweet. Currently used by Twitter’s Promoted Products."
+        ///
+        ///The values are kept as they were given, since Twitter doesn't document what they can be.
+        ///In practice, this is something like `{"followers": false}`.
+        pub scopes: Option<HashMap<String, serde_json::Value>>,
         ///The number of times this tweet has been retweeted (with native retweets).
         pub retweet_count: i32,
         ///Indicates whether the authenticated user has retweeted this tweet.
@@ -220,7 +258,7 @@ round_trip! { raw::RawTweet,
         ///full text is longer than 280 characters.
         pub truncated: bool,
         ///The user who posted this tweet. This field will be absent on tweets included as part of a
-        ///`TwitterUser`.
+        ///`TwitterUser`, and on tweets where Twitter only sent the user's ID in `author_id`.
         pub user: Option<Box<user::TwitterUser>>,
         ///If present and `true`, indicates that this tweet has been withheld due to a DMCA complaint.
         pub withheld_copyright: bool,
@@ -231,8 +269,12 @@ round_trip! { raw::RawTweet,
         ///- `XX`: Withheld in all countries
         ///- `XY`: Withheld due to DMCA complaint.
         pub withheld_in_countries: Option<Vec<String>>,
-        ///If present, indicates whether the content being withheld is the `status` or the `user`.
-        pub withheld_scope: Option<String>,
+        ///If present, indicates whether the content being withheld is this tweet, or the user who
+        ///posted it.
+        ///
+        ///When this is `WithheldScope::User`, `withheld_in_countries` lists the countries where the
+        ///whole account is withheld, rather than just this tweet.
+        pub withheld_scope: Option<WithheldScope>,
     }
 }
 
@@ -247,6 +289,11 @@ impl TryFrom<raw::RawTweet> for Tweet {
             .or(raw.text)
             .ok_or(error::Error::MissingValue("text"))?;
         let current_user_retweet = raw.current_user_retweet.map(|cur| cur.id);
+        let (author_id, user) = match raw.user {
+            Some(raw::RawTweetUser::Full(user)) => (Some(user.id), Some(user)),
+            Some(raw::RawTweetUser::Trimmed { id }) => (Some(id), None),
+            None => (None, None),
+        };
 
         if let Some(ref mut range) = raw.display_text_range {
             codepoints_to_bytes(range, &text);
@@ -275,6 +322,9 @@ impl TryFrom<raw::RawTweet> for Tweet {
         }
 
         Ok(Tweet {
+            author_id,
+            card: raw.card,
+            contributors: raw.contributors,
             coordinates: raw.coordinates.map(|coords| coords.coordinates),
             created_at: raw.created_at,
             display_text_range: raw.display_text_range,
@@ -292,12 +342,15 @@ impl TryFrom<raw::RawTweet> for Tweet {
             possibly_sensitive: raw.possibly_sensitive,
             quoted_status_id: raw.quoted_status_id,
             quoted_status: raw.quoted_status,
+            quote_count: raw.quote_count,
+            reply_count: raw.reply_count,
+            scopes: raw.scopes,
             retweet_count: raw.retweet_count,
             retweeted: raw.retweeted,
             retweeted_status: raw.retweeted_status,
             source: raw.source,
             truncated: raw.truncated,
-            user: raw.user,
+            user,
             withheld_copyright: raw.withheld_copyright,
             withheld_in_countries: raw.withheld_in_countries,
             withheld_scope: raw.withheld_scope,
@@ -307,6 +360,225 @@ impl TryFrom<raw::RawTweet> for Tweet {
     }
 }
 
+impl Tweet {
+    ///Returns a link to this tweet on twitter.com.
+    ///
+    ///If this tweet is a native retweet, the link points at the original tweet in
+    ///`retweeted_status`, since that's the one people will want to see. If the tweet was loaded
+    ///without its `user` (for example as the `status` of a `TwitterUser`), the link uses Twitter's
+    ///generic `i/web/status` form, which redirects to the right account.
+    pub fn permalink(&self) -> String {
+        if let Some(ref original) = self.retweeted_status {
+            return original.permalink();
+        }
+
+        match self.user {
+            Some(ref user) => format!(
+                "https://twitter.com/{}/status/{}",
+                user.screen_name, self.id
+            ),
+            None => format!("https://twitter.com/i/web/status/{}", self.id),
+        }
+    }
+
+    ///Returns the complete text of this tweet, even if it's a retweet of a long tweet.
+    ///
+    ///When a tweet is retweeted, Twitter builds the retweet's `text` by prepending `RT @user: ` to
+    ///the original text, and cuts it off with an ellipsis if the result would be too long. For
+    ///native retweets, this rebuilds the text from the complete text of the tweet in
+    ///`retweeted_status` instead. For all other tweets, or if the original tweet was loaded without
+    ///its `user`, this returns `text` as-is.
+    pub fn full_text(&self) -> Cow<'_, str> {
+        match self.retweeted_status {
+            Some(ref original) => match original.user {
+                Some(ref user) => Cow::Owned(format!(
+                    "RT @{}: {}",
+                    user.screen_name,
+                    original.full_text()
+                )),
+                None => Cow::Borrowed(&self.text),
+            },
+            None => Cow::Borrowed(&self.text),
+        }
+    }
+
+    ///Returns the native poll attached to this tweet, if there is one.
+    ///
+    ///Polls are stored in the tweet's `card`, which is only loaded by `show`, `lookup`, and
+    ///`lookup_map`. For tweets loaded any other way, this returns `None` even if the tweet has a
+    ///poll.
+    pub fn poll(&self) -> Option<Poll> {
+        self.card.as_ref()?.poll()
+    }
+
+    ///Returns whether this tweet is withheld in the country with the given two-letter country
+    ///code.
+    ///
+    ///This also returns `true` if the tweet is withheld in all countries. To see whether it's the
+    ///tweet itself or its author being withheld, check `withheld_scope`.
+    pub fn is_withheld_in(&self, country: &str) -> bool {
+        withheld_in(self.withheld_in_countries.as_deref(), country)
+    }
+
+    ///Returns the part of this tweet's text that's meant to be shown, according to
+    ///`display_text_range`.
+    ///
+    ///For replies, this leaves out the `@mentions` at the start that Twitter adds to the text, and
+    ///for tweets with attached media, it leaves out the `t.co` link to the media at the end. The
+    ///range is converted to bytes when the tweet is loaded, so this is a plain slice of `text`. If
+    ///the tweet doesn't have a display range, this returns all of `text`.
+    pub fn display_text(&self) -> &str {
+        self.display_text_range
+            .and_then(|(start, end)| self.text.get(start..end))
+            .unwrap_or(&self.text)
+    }
+
+    ///Returns `display_text` with the `t.co` links in it replaced by the URLs they point to.
+    ///
+    ///Each link is replaced by the `expanded_url` of its entity, which is how Twitter's own apps
+    ///show links in tweets. Links outside the display range, like the media link that's added to
+    ///the end of a tweet, are left out along with the rest of the hidden text. Tweets with several
+    ///photos give each photo the same link, which is only replaced once.
+    pub fn expanded_text(&self) -> String {
+        let (start, end) = match self.display_text_range {
+            Some((start, end)) if self.text.get(start..end).is_some() => (start, end),
+            _ => (0, self.text.len()),
+        };
+
+        let mut links = self
+            .urls()
+            .iter()
+            .map(|url| (url.range, url.expanded_url.as_deref().unwrap_or(&url.url)))
+            .chain(
+                self.media()
+                    .iter()
+                    .map(|media| (media.range, media.expanded_url.as_str())),
+            )
+            .filter(|&((link_start, link_end), _)| {
+                start <= link_start
+                    && link_end <= end
+                    && self.text.get(link_start..link_end).is_some()
+            })
+            .collect::<Vec<_>>();
+        links.sort_by_key(|&(range, _)| range);
+        links.dedup_by_key(|&mut (range, _)| range);
+
+        let mut text = String::with_capacity(end - start);
+        let mut last = start;
+        for ((link_start, link_end), expanded) in links {
+            // skip anything that overlaps a link that was already replaced
+            if link_start < last {
+                continue;
+            }
+            text.push_str(&self.text[last..link_start]);
+            text.push_str(expanded);
+            last = link_end;
+        }
+        text.push_str(&self.text[last..end]);
+        text
+    }
+
+    ///Returns the hashtags parsed from this tweet.
+    ///
+    ///Like every entity on a `Tweet`, the `range` of each hashtag is in bytes, so it can be used
+    ///to slice `text` directly. Twitter sends these ranges as character counts, which are converted
+    ///when the tweet is loaded.
+    pub fn hashtags(&self) -> &[entities::HashtagEntity] {
+        &self.entities.hashtags
+    }
+
+    ///Returns the users mentioned in this tweet.
+    pub fn mentions(&self) -> &[entities::MentionEntity] {
+        &self.entities.user_mentions
+    }
+
+    ///Returns the links in this tweet, not counting links to attached media.
+    pub fn urls(&self) -> &[entities::UrlEntity] {
+        &self.entities.urls
+    }
+
+    ///Returns the photos, GIF, or video attached to this tweet.
+    ///
+    ///This reads from `extended_entities` when it's present, since `entities` only holds the first
+    ///photo of a set or a thumbnail of a GIF or video.
+    pub fn media(&self) -> &[entities::MediaEntity] {
+        match (&self.extended_entities, &self.entities.media) {
+            (Some(extended), _) => &extended.media,
+            (None, Some(media)) => media,
+            (None, None) => &[],
+        }
+    }
+
+    ///If Twitter only sent the ID of the user who posted this tweet, loads their full profile into
+    ///`user`.
+    ///
+    ///This also fills in the authors of `retweeted_status` and `quoted_status` if they were
+    ///trimmed as well. If no author is missing, this returns without making a network call. To
+    ///fill in the authors of many tweets, use `hydrate_authors` instead, which loads them together.
+    pub async fn hydrate_author(&mut self, token: &auth::Token) -> Result<()> {
+        hydrate_authors(std::slice::from_mut(self), token).await
+    }
+
+    ///Adds the IDs of any authors of this tweet and the tweets it contains that were only sent as
+    ///an ID to the given list.
+    fn trimmed_authors(&self, ids: &mut Vec<u64>) {
+        if self.user.is_none() {
+            ids.extend(self.author_id);
+        }
+        if let Some(ref original) = self.retweeted_status {
+            original.trimmed_authors(ids);
+        }
+        if let Some(ref quoted) = self.quoted_status {
+            quoted.trimmed_authors(ids);
+        }
+    }
+
+    ///Fills in any authors of this tweet and the tweets it contains that were only sent as an ID,
+    ///using the given users.
+    fn fill_authors(&mut self, users: &HashMap<u64, user::TwitterUser>) {
+        if self.user.is_none() {
+            if let Some(user) = self.author_id.and_then(|id| users.get(&id)) {
+                self.user = Some(Box::new(user.clone()));
+            }
+        }
+        if let Some(ref mut original) = self.retweeted_status {
+            original.fill_authors(users);
+        }
+        if let Some(ref mut quoted) = self.quoted_status {
+            quoted.fill_authors(users);
+        }
+    }
+}
+
+///An account that helped write a tweet, as listed in `Tweet::contributors`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Contributor {
+    ///The numeric ID of the contributing account.
+    pub id: u64,
+    ///The screen name of the contributing account.
+    pub screen_name: String,
+}
+
+///Represents what content is being withheld, when a tweet or user is withheld in some countries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WithheldScope {
+    ///Only the given tweet is being withheld.
+    #[serde(rename = "status")]
+    Status,
+    ///The user's whole account is being withheld.
+    #[serde(rename = "user")]
+    User,
+}
+
+///Checks the given `withheld_in_countries` list for the given country code, or the `XX` code for
+///all countries.
+pub(crate) fn withheld_in(countries: Option<&[String]>, country: &str) -> bool {
+    countries
+        .unwrap_or_default()
+        .iter()
+        .any(|c| c == "XX" || c.eq_ignore_ascii_case(country))
+}
+
 ///Represents the app from which a specific tweet was posted.
 ///
 ///This struct is parsed out of the HTML anchor tag that Twitter returns as part of each tweet.
@@ -376,7 +648,10 @@ where
 ///Note that for media attached to a tweet, this struct will only contain the first image of a
 ///photo set, or a thumbnail of a video or GIF. Full media information is available in the tweet's
 ///`extended_entities` field.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+///If the tweet was loaded with entities turned off, as with `SearchBuilder::include_entities`,
+///all of these collections are empty.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct TweetEntities {
     ///Collection of hashtags parsed from the tweet.
     pub hashtags: Vec<entities::HashtagEntity>,
@@ -477,8 +752,12 @@ pub struct ExtendedTweetEntities {
 /// available that were posted after the given ID". The earlier invocations of `older` with `None`
 /// do not place a bound on the tweets it loads. `newer` operates in a similar fashion with its
 /// argument, saying "newer than what I just returned, but not newer than this given ID". When
-/// called like this, it's possible for these methods to return nothing, which will also clear the
-/// `Timeline`'s tracked IDs.
+/// called like this, it's possible for these methods to return nothing.
+///
+/// When a call returns no tweets, the `Timeline` keeps the IDs it was tracking. An empty result
+/// from `older` means you've reached the end of the timeline, and calling it again will keep
+/// returning nothing rather than starting over at the newest tweets. An empty result from `newer`
+/// means nothing new has been posted yet, so you can keep calling `newer` to poll for new tweets.
 ///
 /// If you want to manually pull tweets between certain IDs, the baseline `call` function can do
 /// that for you. Keep in mind, though, that `call` doesn't update the `min_id` or `max_id` fields,
@@ -508,34 +787,24 @@ impl Timeline {
     }
 
     ///Clear the saved IDs on this timeline, and return the most recent set of tweets.
-    pub fn start(mut self) -> TimelineFuture {
+    pub async fn start(mut self) -> Result<(Timeline, Response<Vec<Tweet>>)> {
         self.reset();
 
-        self.older(None)
+        self.older(None).await
     }
 
     ///Return the set of tweets older than the last set pulled, optionally placing a minimum tweet
     ///ID to bound with.
-    pub fn older(self, since_id: Option<u64>) -> TimelineFuture {
+    pub async fn older(self, since_id: Option<u64>) -> Result<(Timeline, Response<Vec<Tweet>>)> {
         let req = self.request(since_id, self.min_id.map(|id| id - 1));
-        let loader = Box::pin(request_with_json_response(req));
-
-        TimelineFuture {
-            timeline: Some(self),
-            loader,
-        }
+        self.load(req).await
     }
 
     ///Return the set of tweets newer than the last set pulled, optionall placing a maximum tweet
     ///ID to bound with.
-    pub fn newer(self, max_id: Option<u64>) -> TimelineFuture {
+    pub async fn newer(self, max_id: Option<u64>) -> Result<(Timeline, Response<Vec<Tweet>>)> {
         let req = self.request(self.max_id, max_id);
-        let loader = Box::pin(request_with_json_response(req));
-
-        TimelineFuture {
-            timeline: Some(self),
-            loader,
-        }
+        self.load(req).await
     }
 
     ///Return the set of tweets between the IDs given.
@@ -577,10 +846,22 @@ impl Timeline {
         }
     }
 
+    ///Load the given request, and update the min_id and max_id on self with the result.
+    async fn load(mut self, req: Request<Body>) -> Result<(Timeline, Response<Vec<Tweet>>)> {
+        let resp = request_with_json_response::<Vec<Tweet>>(req).await?;
+        self.map_ids(&resp.response);
+        Ok((self, resp))
+    }
+
     ///With the returned slice of Tweets, set the min_id and max_id on self.
+    ///
+    ///An empty page leaves the IDs alone, so that calling `older` or `newer` again asks for the
+    ///same range instead of starting over from the newest tweets.
     fn map_ids(&mut self, resp: &[Tweet]) {
-        self.max_id = resp.first().map(|status| status.id);
-        self.min_id = resp.last().map(|status| status.id);
+        if let (Some(first), Some(last)) = (resp.first(), resp.last()) {
+            self.max_id = Some(first.id);
+            self.min_id = Some(last.id);
+        }
     }
 
     ///Create an instance of `Timeline` with the given link and tokens.
@@ -600,36 +881,6 @@ impl Timeline {
     }
 }
 
-/// `Future` which represents loading from a `Timeline`.
-///
-/// When this future completes, it will either return the tweets given by Twitter (after having
-/// updated the IDs in the parent `Timeline`) or the error encountered when loading or parsing the
-/// response.
-#[must_use = "futures do nothing unless polled"]
-pub struct TimelineFuture {
-    timeline: Option<Timeline>,
-    loader: FutureResponse<Vec<Tweet>>,
-}
-
-impl Future for TimelineFuture {
-    type Output = Result<(Timeline, Response<Vec<Tweet>>)>;
-
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
-        match Pin::new(&mut self.loader).poll(cx) {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
-            Poll::Ready(Ok(resp)) => {
-                if let Some(mut timeline) = self.timeline.take() {
-                    timeline.map_ids(&resp.response);
-                    Poll::Ready(Ok((timeline, resp)))
-                } else {
-                    Poll::Ready(Err(error::Error::FutureAlreadyCompleted))
-                }
-            }
-        }
-    }
-}
-
 /// Represents an in-progress tweet before it is sent.
 ///
 /// This is your entry point to posting new tweets to Twitter. To begin, make a new `DraftTweet` by
@@ -791,6 +1042,28 @@ impl DraftTweet {
         }
     }
 
+    ///Makes this draft a quote tweet of the tweet with the given ID.
+    ///
+    ///This sets `attachment_url` to the quoted tweet's URL, so the quoted tweet is attached to
+    ///the draft instead of being linked in its text. This means the URL doesn't count against the
+    ///280 character text limit, and the full limit is still available for the draft's text.
+    ///Calling this replaces any `attachment_url` set before.
+    ///
+    ///```rust,no_run
+    ///# use egg_mode::Token;
+    ///# #[tokio::main]
+    ///# async fn main() {
+    ///# let token: Token = unimplemented!();
+    ///use egg_mode::tweet::DraftTweet;
+    ///
+    ///let draft = DraftTweet::new("this is a good tweet").quote(782349500404862976);
+    ///draft.send(&token).await.unwrap();
+    ///# }
+    ///```
+    pub fn quote(self, id: u64) -> Self {
+        self.attachment_url(format!("https://twitter.com/i/web/status/{}", id))
+    }
+
     ///Attach a lat/lon coordinate to this tweet, and mark whether a pin should be placed on the
     ///exact coordinate when the tweet is displayed.
     ///
@@ -840,7 +1113,23 @@ impl DraftTweet {
     }
 
     ///Send the assembled tweet as the authenticated user.
+    ///
+    ///If the user has already posted a tweet with the same text, Twitter refuses to post it again,
+    ///and this returns a `DuplicateStatus` error. In that case, this also loads the user's 200 most
+    ///recent tweets to find the earlier one, and puts its ID in the error if it was there.
     pub async fn send(&self, token: &auth::Token) -> Result<Response<Tweet>> {
+        let params = self.params();
+        let req = post(links::statuses::UPDATE, token, Some(&params));
+        match request_with_json_response(req).await {
+            Err(error::Error::DuplicateStatus(None)) => Err(error::Error::DuplicateStatus(
+                self.find_duplicate(token).await,
+            )),
+            res => res,
+        }
+    }
+
+    ///Assembles the parameters that `send` posts to Twitter for this draft.
+    fn params(&self) -> ParamList {
         let mut params = ParamList::new()
             .add_param("status", self.text.clone())
             .add_opt_param("in_reply_to_status_id", self.in_reply_to.map_string())
@@ -880,9 +1169,40 @@ impl DraftTweet {
             params.add_param_ref("media_ids", media);
         }
 
-        let req = post(links::statuses::UPDATE, token, Some(&params));
-        request_with_json_response(req).await
+        params
     }
+
+    ///Looks through the authenticated user's most recent tweets for one with the same text as
+    ///this draft, returning its ID if one was found. Any error while loading the tweets is
+    ///ignored, since this is only used to add detail to a `DuplicateStatus` error.
+    async fn find_duplicate(&self, token: &auth::Token) -> Option<u64> {
+        let params = ParamList::new()
+            .extended_tweets()
+            .add_param("include_rts", "false");
+        let timeline =
+            Timeline::new(links::statuses::USER_TIMELINE, Some(params), token).with_page_size(200);
+        let (_, tweets) = timeline.start().await.ok()?;
+
+        let text = self.text.trim();
+        tweets
+            .iter()
+            .find(|tweet| tweet.text.trim() == text)
+            .map(|tweet| tweet.id)
+    }
+}
+
+///The error returned when `post_thread` stops partway through a thread.
+///
+///Since the tweets before the failure were already posted, they're returned here along with the
+///error, so you can tell how far the thread got, and continue it or delete it.
+#[derive(Debug, thiserror::Error)]
+#[error("Thread stopped after {} tweets: {}", posted.len(), error)]
+pub struct PostThreadError {
+    ///The tweets that were posted before the error, in thread order.
+    pub posted: Vec<Tweet>,
+    ///The error that stopped the thread.
+    #[source]
+    pub error: error::Error,
 }
 
 #[cfg(test)]
@@ -903,6 +1223,10 @@ mod tests {
 
         assert_eq!(sample.text,
                    ".@Serrayak said he’d use what-ev-er I came up with as his Halloween avatar so I’m just making sure you all know he said that https://t.co/MvgxCwDwSa");
+        assert_eq!(
+            sample.permalink(),
+            "https://twitter.com/0xabad1dea/status/782349500404862976"
+        );
         assert!(sample.user.is_some());
         assert_eq!(sample.user.unwrap().screen_name, "0xabad1dea");
         assert_eq!(sample.id, 782349500404862976);
@@ -955,6 +1279,301 @@ mod tests {
         load_tweet("sample_payloads/nullable_user_mention.json");
     }
 
+    #[test]
+    fn parse_engagement_counts() {
+        let sample = load_tweet("sample_payloads/nullable_user_mention.json");
+        assert_eq!(sample.quote_count, Some(0));
+        assert_eq!(sample.reply_count, Some(0));
+
+        let sample = load_tweet("sample_payloads/sample-reply.json");
+        assert_eq!(sample.quote_count, None);
+        assert_eq!(sample.reply_count, None);
+    }
+
+    #[test]
+    fn parse_poll() {
+        let mut sample = load_tweet("sample_payloads/sample-reply.json");
+        assert!(sample.poll().is_none());
+
+        sample.card = serde_json::from_value(serde_json::json!({
+            "name": "poll3choice_text_only",
+            "url": "https://twitter.com",
+            "binding_values": {
+                "choice1_label": { "type": "STRING", "string_value": "yes" },
+                "choice1_count": { "type": "STRING", "string_value": "12" },
+                "choice2_label": { "type": "STRING", "string_value": "no" },
+                "choice2_count": { "type": "STRING", "string_value": "3" },
+                "choice3_label": { "type": "STRING", "string_value": "maybe" },
+                "choice3_count": { "type": "STRING", "string_value": "0" },
+                "end_datetime_utc": { "type": "STRING", "string_value": "2016-10-07T19:16:56Z" },
+                "counts_are_final": { "type": "BOOLEAN", "boolean_value": true }
+            }
+        }))
+        .unwrap();
+
+        let poll = sample.poll().unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("yes".to_string(), 12),
+                ("no".to_string(), 3),
+                ("maybe".to_string(), 0)
+            ]
+        );
+        assert_eq!(poll.ends_at.timestamp(), 1475867816);
+        assert!(poll.counts_are_final);
+
+        sample.card.as_mut().unwrap().name = "summary".to_string();
+        assert!(sample.poll().is_none());
+    }
+
+    #[test]
+    fn from_json_str() {
+        let sample = load_file("sample_payloads/sample-reply.json");
+        let tweet = super::from_json_str(&sample).unwrap();
+        assert_eq!(tweet.in_reply_to_status_id, Some(782643731665080322));
+
+        assert!(super::from_json_str("{}").is_err());
+    }
+
+    #[test]
+    fn parse_contributors_scopes() {
+        let sample = load_tweet("sample_payloads/sample-quote.json");
+        assert!(sample.contributors.is_none());
+        assert!(sample.scopes.is_none());
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-quote.json")).unwrap();
+        json["contributors"] = serde_json::json!([
+            { "id": 819797, "id_str": "819797", "screen_name": "episod" }
+        ]);
+        json["scopes"] = serde_json::json!({ "followers": false });
+        let sample: Tweet = serde_json::from_value(json).unwrap();
+
+        let contributors = sample.contributors.as_ref().unwrap();
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].id, 819797);
+        assert_eq!(contributors[0].screen_name, "episod");
+        assert_eq!(sample.scopes.as_ref().unwrap()["followers"], false);
+
+        let json = serde_json::to_value(&sample).unwrap();
+        let roundtrip: Tweet = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip.contributors, sample.contributors);
+    }
+
+    #[test]
+    fn hydrate_trimmed_author() {
+        use std::collections::HashMap;
+
+        let sample = load_tweet("sample_payloads/sample-quote.json");
+        assert_eq!(sample.author_id, sample.user.as_ref().map(|user| user.id));
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-quote.json")).unwrap();
+        let user_id = json["user"]["id"].as_u64().unwrap();
+        json["user"] = serde_json::json!({ "id": user_id, "id_str": user_id.to_string() });
+        let mut sample: Tweet = serde_json::from_value(json).unwrap();
+        assert!(sample.user.is_none());
+        assert_eq!(sample.author_id, Some(user_id));
+
+        let roundtrip: Tweet =
+            serde_json::from_value(serde_json::to_value(&sample).unwrap()).unwrap();
+        assert_eq!(roundtrip.author_id, Some(user_id));
+
+        let mut ids = Vec::new();
+        sample.trimmed_authors(&mut ids);
+        assert_eq!(ids, vec![user_id]);
+
+        let users: Vec<crate::user::TwitterUser> =
+            serde_json::from_str(&load_file("sample_payloads/user_array.json")).unwrap();
+        let mut user = users[0].clone();
+        user.id = user_id;
+        let mut users = HashMap::new();
+        users.insert(user_id, user);
+
+        sample.fill_authors(&users);
+        assert_eq!(sample.user.as_ref().map(|user| user.id), Some(user_id));
+    }
+
+    #[test]
+    fn draft_quote() {
+        let draft = super::DraftTweet::new("look at this").quote(782349500404862976);
+        assert_eq!(draft.text, "look at this");
+        assert_eq!(
+            draft.attachment_url.as_deref(),
+            Some("https://twitter.com/i/web/status/782349500404862976")
+        );
+    }
+
+    #[test]
+    fn draft_reply_geo_params() {
+        let draft = super::DraftTweet::new("@rustlang 100% agree & then some")
+            .in_reply_to(782643731665080322)
+            .auto_populate_reply_metadata(true)
+            .coordinates(37.5, -122.25, true);
+        let params = draft.params();
+
+        assert_eq!(
+            params.get("in_reply_to_status_id").map(|v| &**v),
+            Some("782643731665080322")
+        );
+        assert_eq!(
+            params.get("auto_populate_reply_metadata").map(|v| &**v),
+            Some("true")
+        );
+        assert_eq!(params.get("lat").map(|v| &**v), Some("37.5"));
+        assert_eq!(params.get("long").map(|v| &**v), Some("-122.25"));
+        assert_eq!(
+            params.get("display_coordinates").map(|v| &**v),
+            Some("true")
+        );
+
+        let body = params.to_urlencoded();
+        assert!(body.contains("status=%40rustlang%20100%25%20agree%20%26%20then%20some"));
+
+        let plain = super::DraftTweet::new("hello").params();
+        assert!(plain.get("in_reply_to_status_id").is_none());
+        assert!(plain.get("lat").is_none());
+        assert!(plain.get("long").is_none());
+    }
+
+    #[tokio::test]
+    async fn unlike_not_liked() {
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use hyper::{Method, StatusCode};
+
+        let not_liked = r#"{"errors":[{"code":144,"message":"No status found with that ID."}]}"#;
+        let tweet = load_file("sample_payloads/sample-reply.json");
+        let mock = MockTransport::new()
+            .respond(StatusCode::NOT_FOUND, Headers::new(), not_liked)
+            .respond(StatusCode::OK, Headers::new(), tweet);
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let unliked = with_transport(mock.clone(), super::unlike(782644334671691776, &token))
+            .await
+            .unwrap();
+        assert_eq!(unliked.id, 782644334671691776);
+        assert_eq!(unliked.user.as_ref().unwrap().id, 2977334326);
+
+        // the 144 error is answered by loading the tweet instead
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].uri.path(), "/1.1/favorites/destroy.json");
+        assert_eq!(requests[1].method, Method::GET);
+        assert_eq!(requests[1].uri.path(), "/1.1/statuses/show.json");
+
+        let rate_limited = crate::error::Error::RateLimit(0);
+        assert!(!super::is_not_liked(&rate_limited));
+    }
+
+    #[tokio::test]
+    async fn timeline_paging() {
+        use crate::common::tests::tweet_page;
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use hyper::StatusCode;
+
+        let mut mock = MockTransport::new();
+        for ids in &[&[50, 40][..], &[70, 60], &[90, 80], &[], &[30, 20], &[]] {
+            mock = mock.respond(StatusCode::OK, Headers::new(), tweet_page(ids));
+        }
+        let token = crate::auth::Token::Bearer("abc".to_string());
+        let timeline = super::home_timeline(&token).with_page_size(2);
+
+        let (timeline, feed) = with_transport(mock.clone(), timeline.start())
+            .await
+            .unwrap();
+        assert_eq!(feed.iter().map(|t| t.id).collect::<Vec<_>>(), [50, 40]);
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(50), Some(40)));
+
+        // two pages forward
+        let (timeline, feed) = with_transport(mock.clone(), timeline.newer(None))
+            .await
+            .unwrap();
+        assert_eq!(feed.len(), 2);
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(70), Some(60)));
+        let (timeline, _) = with_transport(mock.clone(), timeline.newer(None))
+            .await
+            .unwrap();
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(90), Some(80)));
+
+        // nothing newer yet, so the IDs stay put for the next poll
+        let (timeline, feed) = with_transport(mock.clone(), timeline.newer(None))
+            .await
+            .unwrap();
+        assert!(feed.is_empty());
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(90), Some(80)));
+
+        // one page back
+        let (timeline, feed) = with_transport(mock.clone(), timeline.older(None))
+            .await
+            .unwrap();
+        assert_eq!(feed.iter().map(|t| t.id).collect::<Vec<_>>(), [30, 20]);
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(30), Some(20)));
+
+        // the end of the timeline doesn't send the next call back to the start
+        let (timeline, feed) = with_transport(mock.clone(), timeline.older(None))
+            .await
+            .unwrap();
+        assert!(feed.is_empty());
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(30), Some(20)));
+
+        let requests = mock
+            .requests()
+            .iter()
+            .map(|r| r.uri.query().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        let bounds = |idx: usize| {
+            let line = &requests[idx];
+            (line.contains("since_id="), line.contains("max_id="))
+        };
+        assert_eq!(requests.len(), 6);
+        assert_eq!(bounds(0), (false, false));
+        assert!(requests[1].contains("since_id=50"));
+        assert!(requests[2].contains("since_id=70"));
+        assert!(requests[3].contains("since_id=90"));
+        assert_eq!(bounds(3), (true, false));
+        assert!(requests[4].contains("max_id=79"));
+        assert!(requests[5].contains("max_id=19"));
+        assert_eq!(bounds(5), (false, true));
+    }
+
+    #[tokio::test]
+    async fn liked_by_pages() {
+        use crate::common::tests::tweet_page;
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use hyper::StatusCode;
+
+        let mock = MockTransport::new()
+            .respond(StatusCode::OK, Headers::new(), tweet_page(&[90, 80]))
+            .respond(StatusCode::OK, Headers::new(), tweet_page(&[70, 60]));
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let timeline = super::liked_by("rustlang", &token).with_page_size(200);
+        let (timeline, feed) = with_transport(mock.clone(), timeline.start())
+            .await
+            .unwrap();
+        assert_eq!(feed.iter().map(|t| t.id).collect::<Vec<_>>(), [90, 80]);
+        let (timeline, feed) = with_transport(mock.clone(), timeline.older(None))
+            .await
+            .unwrap();
+        assert_eq!(feed.iter().map(|t| t.id).collect::<Vec<_>>(), [70, 60]);
+        assert_eq!((timeline.max_id, timeline.min_id), (Some(70), Some(60)));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        for request in &requests {
+            assert_eq!(request.uri.path(), "/1.1/favorites/list.json");
+            let query = request.uri.query().unwrap();
+            assert!(query.split('&').any(|p| p == "screen_name=rustlang"));
+            assert!(query.split('&').any(|p| p == "count=200"));
+        }
+        let first = requests[0].uri.query().unwrap();
+        assert!(!first.contains("max_id=") && !first.contains("since_id="));
+        let second = requests[1].uri.query().unwrap();
+        assert!(second.split('&').any(|p| p == "max_id=79"));
+    }
+
     #[test]
     fn parse_reply() {
         let sample = load_tweet("sample_payloads/sample-reply.json");
@@ -982,6 +1601,13 @@ mod tests {
         let sample = load_tweet("sample_payloads/sample-retweet.json");
 
         assert!(sample.retweeted_status.is_some());
+        assert_eq!(
+            sample.permalink(),
+            "https://twitter.com/andrewismusic/status/782770881193992193"
+        );
+        assert!(sample.text.ends_with('…'));
+        assert_eq!(sample.full_text(),
+                   "RT @andrewismusic: it's working: follow @andrewhuangbot for a random lyric of mine every hour. we'll call this version 0.1.0. wanna get line breaks in there");
         assert_eq!(sample.retweeted_status.unwrap().text,
                    "it's working: follow @andrewhuangbot for a random lyric of mine every hour. we'll call this version 0.1.0. wanna get line breaks in there");
     }
@@ -997,6 +1623,261 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_lookup_map() {
+        let sample = load_file("sample_payloads/sample-reply.json");
+        let tweet: serde_json::Value = serde_json::from_str(&sample).unwrap();
+        let resp = serde_json::json!({
+            "id": {
+                "782643731665080322": null,
+                "782644334671691776": tweet,
+            }
+        });
+
+        let map = super::parse_lookup_map(&resp).unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert!(map[&782643731665080322].is_none());
+        assert_eq!(
+            map[&782644334671691776]
+                .as_ref()
+                .unwrap()
+                .in_reply_to_status_id,
+            Some(782643731665080322)
+        );
+        assert!(super::parse_lookup_map(&serde_json::json!([])).is_err());
+    }
+
+    #[test]
+    fn seen_set_eviction() {
+        let mut seen = super::SeenSet::with_max_size(3);
+
+        assert!(seen.insert(1));
+        assert!(seen.insert(2));
+        assert!(seen.insert(3));
+        assert!(!seen.insert(1));
+        assert!(seen.insert(4));
+
+        assert_eq!(seen.len(), 3);
+        assert!(!seen.contains(2));
+        assert_eq!(seen.ids().collect::<Vec<_>>(), vec![3, 1, 4]);
+
+        let reloaded = seen.ids().collect::<super::SeenSet>();
+        assert_eq!(reloaded.ids().collect::<Vec<_>>(), vec![3, 1, 4]);
+    }
+
+    #[test]
+    fn parse_withheld() {
+        let sample = load_file("sample_payloads/sample-reply.json");
+        let mut json: serde_json::Value = serde_json::from_str(&sample).unwrap();
+        json["withheld_copyright"] = true.into();
+        json["withheld_in_countries"] = serde_json::json!(["DE", "XY"]);
+        json["withheld_scope"] = "status".into();
+
+        let tweet: Tweet = serde_json::from_value(json.clone()).unwrap();
+        assert!(tweet.withheld_copyright);
+        assert_eq!(tweet.withheld_scope, Some(super::WithheldScope::Status));
+        assert!(tweet.is_withheld_in("de"));
+        assert!(!tweet.is_withheld_in("FR"));
+
+        json["withheld_in_countries"] = serde_json::json!(["XX"]);
+        json["withheld_scope"] = "user".into();
+        let tweet: Tweet = serde_json::from_value(json).unwrap();
+        assert_eq!(tweet.withheld_scope, Some(super::WithheldScope::User));
+        assert!(tweet.is_withheld_in("FR"));
+
+        let tweet = load_tweet("sample_payloads/sample-reply.json");
+        assert!(!tweet.withheld_copyright);
+        assert!(tweet.withheld_scope.is_none());
+        assert!(!tweet.is_withheld_in("DE"));
+    }
+
+    #[test]
+    fn thread_ordering() {
+        let root = load_tweet("sample_payloads/sample-reply.json");
+        let reply = |id, parent| {
+            let mut tweet = root.clone();
+            tweet.id = id;
+            tweet.in_reply_to_status_id = Some(parent);
+            tweet
+        };
+
+        let replies = vec![
+            reply(50, 10),
+            reply(40, 30),
+            reply(30, root.id),
+            reply(10, root.id),
+            reply(20, 999),
+        ];
+        let thread = super::thread_order(root.clone(), replies);
+        let ids = thread.iter().map(|t| t.id).collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![root.id, 10, 50, 30, 40]);
+    }
+
+    #[tokio::test]
+    async fn thread_pages() {
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use hyper::StatusCode;
+
+        let sample: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-reply.json")).unwrap();
+        let tweet = |id: u64, parent: u64| {
+            let mut tweet = sample.clone();
+            tweet["id"] = id.into();
+            tweet["id_str"] = id.to_string().into();
+            tweet["in_reply_to_status_id"] = parent.into();
+            tweet["in_reply_to_status_id_str"] = parent.to_string().into();
+            tweet
+        };
+        let page = |statuses: Vec<serde_json::Value>, next_results: Option<&str>| {
+            serde_json::json!({
+                "statuses": statuses,
+                "search_metadata": {
+                    "completed_in": 0.0,
+                    "max_id": 0,
+                    "next_results": next_results,
+                    "query": "to:rustlang",
+                    "count": 100,
+                    "since_id": 100,
+                },
+            })
+            .to_string()
+        };
+
+        let mock = MockTransport::new()
+            .respond(StatusCode::OK, Headers::new(), tweet(100, 1).to_string())
+            .respond(
+                StatusCode::OK,
+                Headers::new(),
+                page(vec![tweet(300, 100), tweet(250, 300)], Some("?max_id=249")),
+            )
+            .respond(
+                StatusCode::OK,
+                Headers::new(),
+                page(vec![tweet(200, 100)], None),
+            );
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let thread = with_transport(mock.clone(), super::thread(100, &token))
+            .await
+            .unwrap();
+        assert_eq!(
+            thread.iter().map(|t| t.id).collect::<Vec<_>>(),
+            [100, 200, 300, 250]
+        );
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        let query = requests[2].uri.query().unwrap();
+        assert!(query.split('&').any(|p| p == "since_id=100"));
+        assert!(query.split('&').any(|p| p == "max_id=249"));
+    }
+
+    #[test]
+    fn entity_accessors() {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-quote.json")).unwrap();
+        // the crab is one character to Twitter, two UTF-16 code units, and four bytes
+        json["full_text"] = "🦀 #rustlang with @rustlang: https://t.co/abc".into();
+        json["display_text_range"] = serde_json::json!([0, 44]);
+        json["entities"] = serde_json::json!({
+            "hashtags": [{"text": "rustlang", "indices": [2, 11]}],
+            "symbols": [],
+            "urls": [{
+                "url": "https://t.co/abc",
+                "expanded_url": "https://www.rust-lang.org/",
+                "display_url": "rust-lang.org",
+                "indices": [28, 44]
+            }],
+            "user_mentions": [{
+                "id": 165262228, "id_str": "165262228",
+                "name": "Rust Language", "screen_name": "rustlang",
+                "indices": [17, 26]
+            }]
+        });
+        let tweet: Tweet = serde_json::from_value(json).unwrap();
+        let slice = |range: (usize, usize)| &tweet.text[range.0..range.1];
+
+        assert_eq!(tweet.hashtags().len(), 1);
+        assert_eq!(tweet.hashtags()[0].range, (5, 14));
+        assert_eq!(slice(tweet.hashtags()[0].range), "#rustlang");
+        assert_eq!(tweet.mentions().len(), 1);
+        assert_eq!(slice(tweet.mentions()[0].range), "@rustlang");
+        assert_eq!(tweet.urls().len(), 1);
+        assert_eq!(slice(tweet.urls()[0].range), "https://t.co/abc");
+        assert!(tweet.media().is_empty());
+
+        // media comes from extended_entities when it's there
+        let tweet = load_tweet("sample_payloads/sample-extended-onepic.json");
+        assert_eq!(tweet.media().len(), 1);
+        assert_eq!(
+            tweet.media()[0].id,
+            tweet.extended_entities.as_ref().unwrap().media[0].id
+        );
+
+        let mut tweet = tweet;
+        tweet.extended_entities = None;
+        assert_eq!(tweet.media().len(), 1);
+        tweet.entities.media = None;
+        assert!(tweet.media().is_empty());
+    }
+
+    #[test]
+    fn display_and_expanded_text() {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&load_file("sample_payloads/sample-extended-onepic.json"))
+                .unwrap();
+        let photo = json["extended_entities"]["media"][0].clone();
+
+        // a reply with two photos, which share the link at the end
+        let text = "@rustlang @serde_rs 🦀 see https://t.co/link and https://t.co/MvgxCwDwSa";
+        json["full_text"] = text.into();
+        json["display_text_range"] = serde_json::json!([20, 47]);
+        json["entities"]["urls"] = serde_json::json!([{
+            "url": "https://t.co/link",
+            "expanded_url": "https://www.rust-lang.org/",
+            "display_url": "rust-lang.org",
+            "indices": [26, 43]
+        }]);
+        json["entities"]["user_mentions"] = serde_json::json!([
+            {"id": 165262228, "id_str": "165262228", "name": "Rust Language",
+             "screen_name": "rustlang", "indices": [0, 9]},
+            {"id": 1, "id_str": "1", "name": "Serde", "screen_name": "serde_rs",
+             "indices": [10, 19]}
+        ]);
+        let mut second = photo.clone();
+        second["id"] = 2.into();
+        second["id_str"] = "2".into();
+        let mut first = photo;
+        first["indices"] = serde_json::json!([48, 71]);
+        second["indices"] = serde_json::json!([48, 71]);
+        json["entities"]["media"] = serde_json::json!([first.clone()]);
+        json["extended_entities"]["media"] = serde_json::json!([first, second]);
+        let tweet: Tweet = serde_json::from_value(json).unwrap();
+
+        assert_eq!(tweet.display_text(), "🦀 see https://t.co/link and");
+        assert_eq!(
+            tweet.expanded_text(),
+            "🦀 see https://www.rust-lang.org/ and"
+        );
+        assert_eq!(tweet.media().len(), 2);
+
+        // without a display range, everything is shown and every link is expanded once
+        let mut tweet = tweet;
+        tweet.display_text_range = None;
+        assert_eq!(tweet.display_text(), text);
+        assert_eq!(
+            tweet.expanded_text(),
+            "@rustlang @serde_rs 🦀 see https://www.rust-lang.org/ and \
+             https://twitter.com/0xabad1dea/status/782349500404862976/photo/1"
+        );
+
+        let plain = load_tweet("sample_payloads/sample-reply.json");
+        assert_eq!(plain.display_text(), plain.text);
+        assert_eq!(plain.expanded_text(), plain.text);
+    }
+
     #[test]
     fn roundtrip_deser() {
         let sample = load_file("sample_payloads/tweet_array.json");
@@ -1007,4 +1888,28 @@ mod tests {
 
         assert_eq!(json1, json2);
     }
+
+    #[test]
+    fn roundtrip_fixtures() {
+        // every single-tweet fixture should come back the same after being saved and reloaded
+        for path in &[
+            "sample_payloads/compatibilityplus_classic_13994.json",
+            "sample_payloads/compatibilityplus_classic_hidden_13797.json",
+            "sample_payloads/compatibilityplus_extended_13997.json",
+            "sample_payloads/extended_classic_14002.json",
+            "sample_payloads/extended_classic_hidden_13761.json",
+            "sample_payloads/extended_extended_14001.json",
+            "sample_payloads/sample-extended-onepic.json",
+            "sample_payloads/sample-image-alt-text.json",
+            "sample_payloads/sample-quote.json",
+            "sample_payloads/sample-reply.json",
+            "sample_payloads/sample-retweet.json",
+        ] {
+            let json1 = serde_json::to_value(load_tweet(path)).unwrap();
+            let roundtrip: Tweet = serde_json::from_value(json1.clone()).unwrap();
+            let json2 = serde_json::to_value(roundtrip).unwrap();
+
+            assert_eq!(json1, json2, "{} changed after a round trip", path);
+        }
+    }
 }
@@ -0,0 +1,247 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::common::*;
+
+///The embed-ready HTML for a tweet, along with some information about it.
+///
+///This is returned by `oembed` and `oembed_many`. The `html` field holds a `<blockquote>` of the
+///tweet, followed by a `<script>` tag that loads Twitter's widget code to render it, unless the
+///script was left out with `OEmbedOptions::omit_script`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OEmbed {
+    ///The URL of the tweet this embed was made for.
+    pub url: String,
+    ///The HTML to place on the page to embed the tweet.
+    pub html: String,
+    ///The display name of the user who posted the tweet.
+    pub author_name: String,
+    ///The URL of the profile of the user who posted the tweet.
+    pub author_url: String,
+    ///The width of the embedded tweet in pixels, if one was set with `OEmbedOptions::max_width`.
+    pub width: Option<u32>,
+    ///The height of the embedded tweet in pixels. Twitter doesn't fill this in, since the height
+    ///isn't known until the tweet is rendered.
+    pub height: Option<u32>,
+    ///How long the embed can be cached before loading it again, in seconds, if Twitter gave a
+    ///suggestion.
+    #[serde(default, deserialize_with = "deserialize_cache_age")]
+    pub cache_age: Option<u64>,
+}
+
+///Twitter sends `cache_age` as a string of digits, but it's serialized back out as a number, so
+///this accepts either.
+fn deserialize_cache_age<'de, D>(ser: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CacheAge {
+        Number(u64),
+        Text(String),
+    }
+
+    match Option::<CacheAge>::deserialize(ser)? {
+        Some(CacheAge::Number(age)) => Ok(Some(age)),
+        Some(CacheAge::Text(age)) => age.parse().map(Some).map_err(D::Error::custom),
+        None => Ok(None),
+    }
+}
+
+///Represents how an embedded tweet should be floated on the page, as given to
+///`OEmbedOptions::align`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OEmbedAlign {
+    ///Float the tweet to the left, letting text flow around its right side.
+    Left,
+    ///Float the tweet to the right, letting text flow around its left side.
+    Right,
+    ///Center the tweet on its own line.
+    Center,
+    ///Don't float the tweet at all.
+    None,
+}
+
+///Display impl that turns the variants into strings that can be used as oEmbed parameters.
+impl fmt::Display for OEmbedAlign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OEmbedAlign::Left => write!(f, "left"),
+            OEmbedAlign::Right => write!(f, "right"),
+            OEmbedAlign::Center => write!(f, "center"),
+            OEmbedAlign::None => write!(f, "none"),
+        }
+    }
+}
+
+///Represents the color scheme of an embedded tweet, as given to `OEmbedOptions::theme`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OEmbedTheme {
+    ///Dark text on a light background. This is the default.
+    Light,
+    ///Light text on a dark background.
+    Dark,
+}
+
+///Display impl that turns the variants into strings that can be used as oEmbed parameters.
+impl fmt::Display for OEmbedTheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OEmbedTheme::Light => write!(f, "light"),
+            OEmbedTheme::Dark => write!(f, "dark"),
+        }
+    }
+}
+
+///Options for how to render a tweet in `oembed` and `oembed_many`.
+///
+///The default options embed the tweet with its media and the tweet it replied to, and include
+///Twitter's widget script in the HTML.
+#[derive(Debug, Clone, Default)]
+pub struct OEmbedOptions {
+    max_width: Option<u32>,
+    hide_media: bool,
+    hide_thread: bool,
+    omit_script: bool,
+    align: Option<OEmbedAlign>,
+    theme: Option<OEmbedTheme>,
+}
+
+impl OEmbedOptions {
+    ///Creates a new set of options with the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Sets the largest width of the embedded tweet, in pixels. Twitter accepts values between 220
+    ///and 550.
+    pub fn max_width(self, max_width: u32) -> Self {
+        OEmbedOptions {
+            max_width: Some(max_width),
+            ..self
+        }
+    }
+
+    ///Sets whether to leave out photos, videos, and link previews attached to the tweet.
+    pub fn hide_media(self, hide_media: bool) -> Self {
+        OEmbedOptions { hide_media, ..self }
+    }
+
+    ///Sets whether to leave out the tweet that this tweet is replying to, if any.
+    pub fn hide_thread(self, hide_thread: bool) -> Self {
+        OEmbedOptions {
+            hide_thread,
+            ..self
+        }
+    }
+
+    ///Sets whether to leave the `<script>` tag that loads Twitter's widget code out of the HTML.
+    ///
+    ///When embedding several tweets on one page, the script only needs to be included once, so
+    ///this can be set for all but one of them.
+    pub fn omit_script(self, omit_script: bool) -> Self {
+        OEmbedOptions {
+            omit_script,
+            ..self
+        }
+    }
+
+    ///Sets how the embedded tweet is floated on the page. By default, Twitter doesn't float it.
+    pub fn align(self, align: OEmbedAlign) -> Self {
+        OEmbedOptions {
+            align: Some(align),
+            ..self
+        }
+    }
+
+    ///Sets the color scheme of the embedded tweet. By default, Twitter uses `OEmbedTheme::Light`.
+    pub fn theme(self, theme: OEmbedTheme) -> Self {
+        OEmbedOptions {
+            theme: Some(theme),
+            ..self
+        }
+    }
+
+    pub(crate) fn params(&self, url: String) -> ParamList {
+        let mut params = ParamList::new()
+            .add_param("url", url)
+            .add_opt_param("maxwidth", self.max_width.map_string())
+            .add_opt_param("align", self.align.map_string())
+            .add_opt_param("theme", self.theme.map_string());
+        if self.hide_media {
+            params.add_param_ref("hide_media", "true");
+        }
+        if self.hide_thread {
+            params.add_param_ref("hide_thread", "true");
+        }
+        if self.omit_script {
+            params.add_param_ref("omit_script", "true");
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::load_file;
+
+    #[test]
+    fn parse_oembed() {
+        let sample = load_file("sample_payloads/oembed.json");
+        let embed: OEmbed = serde_json::from_str(&sample).unwrap();
+
+        assert_eq!(
+            embed.url,
+            "https://twitter.com/rustlang/status/1261253754969640960"
+        );
+        assert_eq!(embed.author_name, "Rust Language");
+        assert_eq!(embed.author_url, "https://twitter.com/rustlang");
+        assert_eq!(embed.width, Some(550));
+        assert_eq!(embed.height, None);
+        assert_eq!(embed.cache_age, Some(3153600000));
+
+        // the JSON escapes are undone, but the HTML entities are left for the browser
+        assert!(embed
+            .html
+            .starts_with("<blockquote class=\"twitter-tweet\" data-theme=\"dark\""));
+        assert!(embed.html.contains("Rust 1.43.1 &amp; more"));
+        assert!(embed.html.contains("</p>&mdash; Rust Language (@rustlang)"));
+        assert!(embed.html.contains("<script async"));
+
+        let json = serde_json::to_string(&embed).unwrap();
+        let roundtrip: OEmbed = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.html, embed.html);
+        assert_eq!(roundtrip.cache_age, embed.cache_age);
+    }
+
+    #[test]
+    fn oembed_params() {
+        let url = || "https://twitter.com/i/status/1".to_string();
+
+        let plain = OEmbedOptions::new().params(url());
+        assert_eq!(plain.get("url").unwrap(), "https://twitter.com/i/status/1");
+        assert!(plain.get("align").is_none());
+        assert!(plain.get("theme").is_none());
+        assert!(plain.get("hide_media").is_none());
+
+        let styled = OEmbedOptions::new()
+            .max_width(325)
+            .hide_thread(true)
+            .align(OEmbedAlign::Center)
+            .theme(OEmbedTheme::Dark)
+            .params(url());
+        assert_eq!(styled.get("maxwidth").unwrap(), "325");
+        assert_eq!(styled.get("hide_thread").unwrap(), "true");
+        assert_eq!(styled.get("align").unwrap(), "center");
+        assert_eq!(styled.get("theme").unwrap(), "dark");
+    }
+}
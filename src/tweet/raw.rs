@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::{place, user};
 use chrono;
 use serde::Deserialize;
@@ -5,16 +7,20 @@ use serde::Deserialize;
 use crate::common::serde_datetime;
 
 use super::{
-    deserialize_tweet_source, ExtendedTweetEntities, FilterLevel, Tweet, TweetEntities, TweetSource,
+    deserialize_tweet_source, Card, Contributor, ExtendedTweetEntities, FilterLevel, Tweet,
+    TweetEntities, TweetSource, WithheldScope,
 };
 
 #[derive(Debug, Clone, Deserialize)]
 pub(crate) struct RawTweet {
+    pub card: Option<Card>,
+    pub contributors: Option<Vec<Contributor>>,
     pub coordinates: Option<RawCoordinates>,
     #[serde(with = "serde_datetime")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub current_user_retweet: Option<CurrentUserRetweet>,
     pub display_text_range: Option<(usize, usize)>,
+    #[serde(default)]
     pub entities: TweetEntities,
     pub extended_entities: Option<ExtendedTweetEntities>,
     pub extended_tweet: Option<RawExtendedTweet>,
@@ -30,6 +36,9 @@ pub(crate) struct RawTweet {
     pub possibly_sensitive: Option<bool>,
     pub quoted_status_id: Option<u64>,
     pub quoted_status: Option<Box<Tweet>>,
+    pub quote_count: Option<u64>,
+    pub reply_count: Option<u64>,
+    pub scopes: Option<HashMap<String, serde_json::Value>>,
     pub retweet_count: i32,
     pub retweeted: Option<bool>,
     pub retweeted_status: Option<Box<Tweet>>,
@@ -38,11 +47,20 @@ pub(crate) struct RawTweet {
     pub text: Option<String>,
     pub full_text: Option<String>,
     pub truncated: bool,
-    pub user: Option<Box<user::TwitterUser>>,
+    pub user: Option<RawTweetUser>,
     #[serde(default)]
     pub withheld_copyright: bool,
     pub withheld_in_countries: Option<Vec<String>>,
-    pub withheld_scope: Option<String>,
+    pub withheld_scope: Option<WithheldScope>,
+}
+
+///The user who posted a tweet, which is trimmed down to just its ID when loading tweets with
+///`trim_user` set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RawTweetUser {
+    Full(Box<user::TwitterUser>),
+    Trimmed { id: u64 },
 }
 
 #[derive(Debug, Clone, Deserialize)]
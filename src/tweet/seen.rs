@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FromIterator;
+
+use futures::{future, Stream, TryStreamExt};
+
+use crate::common::*;
+use crate::error::Result;
+
+use super::Tweet;
+
+///A record of tweet IDs that have already been seen, to skip repeats across several calls.
+///
+///When polling a timeline or search over and over, it's easy to get the same tweet back more than
+///once, for example when pages overlap or when a process restarts and picks up from an older
+///position. A `SeenSet` keeps track of which tweet IDs have already been handled, so the repeats
+///can be dropped.
+///
+///```rust
+///use egg_mode::tweet::SeenSet;
+///
+///let mut seen = SeenSet::new();
+///
+///assert!(seen.insert(1234));
+///assert!(!seen.insert(1234));
+///```
+///
+///To use this with a page of tweets, like from a `Timeline` or a `SearchResult`, hand the `Vec` to
+///`retain_new`. To use this with a `Stream` of tweets, wrap it with `dedup_tweets`.
+///
+///## Limiting memory use
+///
+///By default, a `SeenSet` remembers every ID it's given. For a long-running process, you can use
+///`with_max_size` to cap the number of IDs it holds. Once the set is full, inserting a new ID
+///forgets the one that was seen least recently.
+///
+///## Saving a `SeenSet`
+///
+///To keep a `SeenSet` across restarts, save the IDs from `ids` and load them back with
+///`collect` or `extend`. `ids` returns them from least- to most-recently seen, so that loading
+///them in order keeps the same eviction order.
+#[derive(Debug, Clone, Default)]
+pub struct SeenSet {
+    stamps: HashMap<u64, u64>,
+    order: BTreeMap<u64, u64>,
+    next_stamp: u64,
+    max_size: Option<usize>,
+}
+
+impl SeenSet {
+    ///Creates a new, empty `SeenSet` with no size limit.
+    pub fn new() -> SeenSet {
+        SeenSet::default()
+    }
+
+    ///Creates a new, empty `SeenSet` that holds at most `max_size` IDs.
+    ///
+    ///A `max_size` of zero is treated as one.
+    pub fn with_max_size(max_size: usize) -> SeenSet {
+        SeenSet {
+            max_size: Some(max_size.max(1)),
+            ..SeenSet::default()
+        }
+    }
+
+    ///Marks the given tweet ID as seen. Returns `true` if the ID had not been seen before.
+    ///
+    ///Seeing an ID again counts as a use for the purposes of the size limit, so it won't be the
+    ///next one forgotten.
+    pub fn insert(&mut self, id: u64) -> bool {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+
+        if let Some(old) = self.stamps.insert(id, stamp) {
+            self.order.remove(&old);
+            self.order.insert(stamp, id);
+            return false;
+        }
+
+        self.order.insert(stamp, id);
+
+        if let Some(max) = self.max_size {
+            while self.stamps.len() > max {
+                let (&oldest, &old_id) = self.order.iter().next().expect("order matches stamps");
+                self.order.remove(&oldest);
+                self.stamps.remove(&old_id);
+            }
+        }
+
+        true
+    }
+
+    ///Returns whether the given tweet ID has been seen, without marking it as seen.
+    pub fn contains(&self, id: u64) -> bool {
+        self.stamps.contains_key(&id)
+    }
+
+    ///Returns the number of IDs currently held.
+    pub fn len(&self) -> usize {
+        self.stamps.len()
+    }
+
+    ///Returns whether no IDs are currently held.
+    pub fn is_empty(&self) -> bool {
+        self.stamps.is_empty()
+    }
+
+    ///Returns the IDs currently held, from least- to most-recently seen.
+    pub fn ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.order.values().copied()
+    }
+
+    ///Removes the tweets from the given `Vec` that have already been seen, and marks the rest as
+    ///seen.
+    pub fn retain_new(&mut self, tweets: &mut Vec<Tweet>) {
+        tweets.retain(|tweet| self.insert(tweet.id));
+    }
+}
+
+impl Extend<u64> for SeenSet {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, ids: I) {
+        for id in ids {
+            self.insert(id);
+        }
+    }
+}
+
+impl FromIterator<u64> for SeenSet {
+    fn from_iter<I: IntoIterator<Item = u64>>(ids: I) -> SeenSet {
+        let mut seen = SeenSet::new();
+        seen.extend(ids);
+        seen
+    }
+}
+
+///Wraps the given `Stream` of tweets so that tweets already in `seen` are skipped.
+///
+///Every tweet that makes it through is added to `seen`, so it can be reused for later calls.
+///Errors from the inner stream are passed through unchanged.
+pub fn dedup_tweets<'a, S>(
+    tweets: S,
+    seen: &'a mut SeenSet,
+) -> impl Stream<Item = Result<Response<Tweet>>> + 'a
+where
+    S: Stream<Item = Result<Response<Tweet>>> + 'a,
+{
+    tweets.try_filter(move |tweet| future::ready(seen.insert(tweet.id)))
+}
@@ -0,0 +1,225 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
+use super::*;
+
+/// The most users `users/lookup` will load at once.
+const LOOKUP_MAX: usize = 100;
+
+/// An in-memory cache of user profiles, to avoid loading the same users over and over.
+///
+/// If you show the same users many times, for example the authors of tweets in a timeline,
+/// calling `show` or `lookup` each time spends rate limit on information you already have. A
+/// `UserCache` holds on to the users it loads, and hands them back without a network call as long
+/// as they're fresh.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// use std::time::Duration;
+/// use egg_mode::user::UserCache;
+///
+/// let mut cache = UserCache::new(&token, 500, Duration::from_secs(15 * 60));
+///
+/// // the first call loads the user from Twitter...
+/// let user = cache.get("rustlang").await.unwrap();
+/// // ...and the second one is served from the cache
+/// let user = cache.get(user.id).await.unwrap();
+/// # }
+/// ```
+///
+/// The cache holds at most `max_size` users. Once it's full, adding a new user forgets the one
+/// that was used least recently. Users that were loaded more than `ttl` ago are loaded again the
+/// next time they're asked for. Users are cached by both ID and screen name, so asking for a user
+/// either way will find them.
+///
+/// Since a cached user doesn't come with new rate-limit information, the `Response` for a cache
+/// hit carries the rate-limit information from the last network call the cache made. If it hasn't
+/// made any yet, every field is `-1`.
+#[derive(Debug, Clone)]
+pub struct UserCache {
+    token: auth::Token,
+    max_size: usize,
+    ttl: Duration,
+    users: HashMap<u64, CachedUser>,
+    names: HashMap<String, u64>,
+    order: BTreeMap<u64, u64>,
+    next_stamp: u64,
+    rate_limit_status: RateLimit,
+}
+
+#[derive(Debug, Clone)]
+struct CachedUser {
+    user: TwitterUser,
+    loaded: Instant,
+    stamp: u64,
+}
+
+impl UserCache {
+    /// Creates a new, empty `UserCache` that loads users with the given token.
+    ///
+    /// The cache holds at most `max_size` users, and loads a user again if it was loaded more than
+    /// `ttl` ago. A `max_size` of zero is treated as one.
+    pub fn new(token: &auth::Token, max_size: usize, ttl: Duration) -> UserCache {
+        UserCache {
+            token: token.clone(),
+            max_size: max_size.max(1),
+            ttl,
+            users: HashMap::new(),
+            names: HashMap::new(),
+            order: BTreeMap::new(),
+            next_stamp: 0,
+            rate_limit_status: RateLimit {
+                limit: -1,
+                remaining: -1,
+                reset: -1,
+            },
+        }
+    }
+
+    /// Returns the given user, loading them with `show` if they aren't cached or have gone stale.
+    pub async fn get<T: Into<UserID>>(&mut self, acct: T) -> Result<Response<TwitterUser>> {
+        let acct = acct.into();
+
+        if let Some(user) = self.get_cached(&acct) {
+            return Ok(Response::new(self.rate_limit_status, user));
+        }
+
+        let resp = show(acct, &self.token).await?;
+        self.rate_limit_status = resp.rate_limit_status;
+        self.insert(resp.response.clone());
+
+        Ok(resp)
+    }
+
+    /// Returns the given users, loading the ones that aren't cached or have gone stale with
+    /// `lookup`.
+    ///
+    /// The users are returned in the order they were asked for. Like `lookup`, users that couldn't
+    /// be loaded are left out. If more than 100 users need to be loaded, they're loaded in batches
+    /// of 100. The rate-limit information is from the last batch that was loaded.
+    pub async fn get_many<T, I>(&mut self, accts: I) -> Result<Response<Vec<TwitterUser>>>
+    where
+        T: Into<UserID>,
+        I: IntoIterator<Item = T>,
+    {
+        let accts = accts.into_iter().map(Into::into).collect::<Vec<UserID>>();
+        let missing = accts
+            .iter()
+            .filter(|acct| self.get_cached(acct).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for batch in missing.chunks(LOOKUP_MAX) {
+            let resp = lookup(batch.iter().cloned(), &self.token).await?;
+            self.rate_limit_status = resp.rate_limit_status;
+            for user in resp.response {
+                self.insert(user);
+            }
+        }
+
+        let users = accts
+            .iter()
+            .filter_map(|acct| self.get_cached(acct))
+            .collect();
+
+        Ok(Response::new(self.rate_limit_status, users))
+    }
+
+    /// Adds the given user to the cache, as if they had just been loaded.
+    ///
+    /// This can be used to fill the cache with users that came from somewhere else, like the
+    /// `user` field of a `Tweet`.
+    pub fn insert(&mut self, user: TwitterUser) {
+        self.remove(&UserID::ID(user.id));
+
+        let stamp = self.next_stamp();
+        self.names.insert(user.screen_name.to_lowercase(), user.id);
+        self.order.insert(stamp, user.id);
+        self.users.insert(
+            user.id,
+            CachedUser {
+                user,
+                loaded: Instant::now(),
+                stamp,
+            },
+        );
+
+        while self.users.len() > self.max_size {
+            let (_, &oldest) = self.order.iter().next().expect("order matches users");
+            self.remove(&UserID::ID(oldest));
+        }
+    }
+
+    /// Removes the given user from the cache, so they're loaded fresh the next time they're asked
+    /// for.
+    pub fn remove(&mut self, acct: &UserID) {
+        if let Some(id) = self.cached_id(acct) {
+            if let Some(cached) = self.users.remove(&id) {
+                self.order.remove(&cached.stamp);
+                self.names.remove(&cached.user.screen_name.to_lowercase());
+            }
+        }
+    }
+
+    /// Removes every user from the cache.
+    pub fn clear(&mut self) {
+        self.users.clear();
+        self.names.clear();
+        self.order.clear();
+    }
+
+    /// Returns the number of users currently cached.
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Returns whether no users are currently cached.
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// Returns the rate-limit information from the last network call this cache made.
+    pub fn rate_limit_status(&self) -> RateLimit {
+        self.rate_limit_status
+    }
+
+    fn next_stamp(&mut self) -> u64 {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        stamp
+    }
+
+    fn cached_id(&self, acct: &UserID) -> Option<u64> {
+        match *acct {
+            UserID::ID(id) => Some(id),
+            UserID::ScreenName(ref name) => self.names.get(&name.to_lowercase()).copied(),
+        }
+    }
+
+    /// Returns the given user if they're cached and fresh, and marks them as recently used.
+    pub(crate) fn get_cached(&mut self, acct: &UserID) -> Option<TwitterUser> {
+        let id = self.cached_id(acct)?;
+        let ttl = self.ttl;
+        let stamp = self.next_stamp();
+
+        let cached = self.users.get_mut(&id)?;
+        if cached.loaded.elapsed() > ttl {
+            return None;
+        }
+
+        self.order.remove(&cached.stamp);
+        self.order.insert(stamp, id);
+        cached.stamp = stamp;
+
+        Some(cached.user.clone())
+    }
+}
@@ -6,6 +6,11 @@ use crate::common::*;
 use crate::error::Result;
 use crate::{auth, cursor, links};
 
+use std::collections::HashMap;
+
+use futures::future::try_join;
+use futures::{stream, Stream, TryStreamExt};
+
 use super::*;
 
 //---Groups of users---
@@ -16,6 +21,17 @@ use super::*;
 /// with `u64`, just screen names with `&str` or `String`, or even a mix of both (by using `UserID`
 /// directly).
 ///
+/// Twitter only allows 100 users to be looked up per call, so if more accounts than that are given,
+/// they will be split into groups of 100 and loaded one group at a time. Each group counts against
+/// the rate limit separately, and the rate-limit information from each call is merged into the
+/// returned `Response` the same way as collecting them into one, with `MergePolicy::LatestReset`.
+/// If no accounts are given, no call is made at all. If any of the calls fails, for example because
+/// the rate limit ran out partway through, the error is returned and the users from earlier groups
+/// are dropped.
+///
+/// The users are returned in the order they were given, as far as possible. Users that couldn't be
+/// loaded, for example because their account was suspended, are left out of the results.
+///
 /// ## Examples
 ///
 /// ```rust,no_run
@@ -78,29 +94,191 @@ where
     T: Into<UserID>,
     I: IntoIterator<Item = T>,
 {
-    let (id_param, name_param) = multiple_names_param(accts);
+    lookup_with(accts, &UserOptions::default(), token).await
+}
 
-    let params = ParamList::new()
-        .extended_tweets()
-        .add_param("user_id", id_param)
-        .add_param("screen_name", name_param);
+/// Look up profile information for several Twitter users, with the given options.
+///
+/// This works the same way as `lookup`, but `options` can be used to leave parts of each profile
+/// out of the response. See the docs for `UserOptions` for details.
+pub async fn lookup_with<T, I>(
+    accts: I,
+    options: &UserOptions,
+    token: &auth::Token,
+) -> Result<Response<Vec<TwitterUser>>>
+where
+    T: Into<UserID>,
+    I: IntoIterator<Item = T>,
+{
+    let accts = accts.into_iter().map(Into::into).collect::<Vec<UserID>>();
+    lookup_at(links::users::LOOKUP, &accts, options, token).await
+}
 
-    let req = post(links::users::LOOKUP, token, Some(&params));
+/// The most users that Twitter will return from a single call to `users/lookup`.
+const LOOKUP_BATCH_SIZE: usize = 100;
 
-    request_with_json_response(req).await
+/// Performs `lookup` against the given URL, one batch of users at a time.
+pub(crate) async fn lookup_at(
+    link: &'static str,
+    accts: &[UserID],
+    options: &UserOptions,
+    token: &auth::Token,
+) -> Result<Response<Vec<TwitterUser>>> {
+    let mut batches = Vec::new();
+
+    for batch in accts.chunks(LOOKUP_BATCH_SIZE) {
+        let (id_param, name_param) = multiple_names_param(batch.iter().cloned());
+
+        let params = options.add_params(
+            ParamList::new()
+                .extended_tweets()
+                .add_param("user_id", id_param)
+                .add_param("screen_name", name_param),
+        );
+
+        let req = post(link, token, Some(&params));
+
+        batches.push(request_with_json_response::<Vec<TwitterUser>>(req).await?);
+    }
+
+    let mut users = Response::map(batches.into_iter().collect(), |batches: Vec<Vec<_>>| {
+        batches.into_iter().flatten().collect::<Vec<_>>()
+    });
+
+    // Twitter doesn't return the users in the order they were asked for, so sort them back into
+    // that order
+    let mut ids = HashMap::new();
+    let mut names = HashMap::new();
+    for (idx, acct) in accts.iter().enumerate() {
+        match acct {
+            UserID::ID(id) => {
+                ids.entry(*id).or_insert(idx);
+            }
+            UserID::ScreenName(name) => {
+                names.entry(name.to_lowercase()).or_insert(idx);
+            }
+        }
+    }
+    users.response.sort_by_key(|user| {
+        let by_id = ids.get(&user.id).copied();
+        let by_name = names.get(&user.screen_name.to_lowercase()).copied();
+        match (by_id, by_name) {
+            (Some(a), Some(b)) => a.min(b),
+            (a, b) => a.or(b).unwrap_or(usize::MAX),
+        }
+    });
+
+    Ok(users)
 }
 
 /// Lookup user information for a single user.
 pub async fn show<T: Into<UserID>>(acct: T, token: &auth::Token) -> Result<Response<TwitterUser>> {
-    let params = ParamList::new()
-        .extended_tweets()
-        .add_user_param(acct.into());
+    show_with(acct, &UserOptions::default(), token).await
+}
+
+/// Lookup user information for a single user, with the given options.
+///
+/// This works the same way as `show`, but `options` can be used to leave parts of the profile out
+/// of the response. See the docs for `UserOptions` for details.
+pub async fn show_with<T: Into<UserID>>(
+    acct: T,
+    options: &UserOptions,
+    token: &auth::Token,
+) -> Result<Response<TwitterUser>> {
+    let params = options.add_params(
+        ParamList::new()
+            .extended_tweets()
+            .add_user_param(acct.into()),
+    );
 
     let req = get(links::users::SHOW, token, Some(&params));
 
     request_with_json_response(req).await
 }
 
+/// Lookup the tweet the given user has pinned to their profile.
+///
+/// This loads the user's profile with `show`, then loads the tweet listed in its
+/// `pinned_tweet_ids` with `tweet::show`. If the user hasn't pinned a tweet, the future returned
+/// by this function yields `None`, along with the rate-limit information from the profile lookup.
+/// Otherwise, the rate-limit information is from the tweet lookup.
+pub async fn pinned_tweet<T: Into<UserID>>(
+    acct: T,
+    token: &auth::Token,
+) -> Result<Response<Option<tweet::Tweet>>> {
+    let user = show(acct, token).await?;
+
+    match user.pinned_tweet_ids.first() {
+        Some(&id) => Ok(Response::map(tweet::show(id, token).await?, Some)),
+        None => Ok(Response::map(user, |_| None)),
+    }
+}
+
+/// Lookup user information for a single user along with their most recent tweets.
+///
+/// This loads the user with `show` and their latest `count` tweets from `tweet::user_timeline` at
+/// the same time, which is the information a profile page needs. The tweets include the user's
+/// replies and retweets, newest first. If more than 200 tweets are asked for, they're loaded over
+/// several calls, and if the user doesn't have `count` tweets, all of them are returned.
+///
+/// If either lookup fails, its error is returned, even if the other one succeeded. The rate-limit
+/// information is from whichever call had the fewest calls remaining. Note that `users/show` and
+/// `statuses/user_timeline` are rate-limited separately.
+pub async fn profile_with_tweets<T: Into<UserID>>(
+    acct: T,
+    count: usize,
+    token: &auth::Token,
+) -> Result<Response<(TwitterUser, Vec<tweet::Tweet>)>> {
+    let acct = acct.into();
+    let (user, (tweets, timeline_limit)) =
+        try_join(show(acct.clone(), token), latest_tweets(acct, count, token)).await?;
+
+    let rate_limit_status = lowest_remaining(Some(user.rate_limit_status), timeline_limit);
+    Ok(Response::new(rate_limit_status, (user.response, tweets)))
+}
+
+/// Loads up to `count` of the given user's most recent tweets, along with the rate-limit
+/// information from the page that had the fewest calls remaining.
+async fn latest_tweets(
+    acct: UserID,
+    count: usize,
+    token: &auth::Token,
+) -> Result<(Vec<tweet::Tweet>, Option<RateLimit>)> {
+    let mut tweets = Vec::with_capacity(count);
+    let mut rate_limit_status = None;
+
+    if count == 0 {
+        return Ok((tweets, rate_limit_status));
+    }
+
+    let page_size = count.min(200) as i32;
+    let (mut timeline, mut page) = tweet::user_timeline(acct, true, true, token)
+        .with_page_size(page_size)
+        .start()
+        .await?;
+
+    loop {
+        rate_limit_status = Some(lowest_remaining(
+            rate_limit_status,
+            Some(page.rate_limit_status),
+        ));
+        if page.response.is_empty() {
+            break;
+        }
+        tweets.extend(page.response);
+        if tweets.len() >= count {
+            break;
+        }
+
+        let (next_timeline, next_page) = timeline.older(None).await?;
+        timeline = next_timeline;
+        page = next_page;
+    }
+
+    tweets.truncate(count);
+    Ok((tweets, rate_limit_status))
+}
+
 /// Lookup the user IDs that the authenticating user has disabled retweets from.
 ///
 /// Use `update_follow` to enable/disable viewing retweets from a specific user.
@@ -159,6 +337,71 @@ where
     request_with_json_response(req).await
 }
 
+/// Sort the followers of the given account by whether the account follows them back.
+///
+/// This loads the full `followers_ids` and `friends_ids` lists for the account and compares them,
+/// returning which followers the account follows back (`mutuals`), which ones it doesn't
+/// (`fans`), and which accounts it follows that don't follow it back (`following_only`). Since
+/// this only loads user IDs, it's much cheaper than checking each account with `relation_lookup`.
+///
+/// ## Rate limits
+///
+/// Each of these lists is loaded 5000 IDs at a time, and both `followers/ids` and `friends/ids`
+/// allow 15 calls per 15-minute window. An account with more than 75,000 followers or friends
+/// can't be classified within one window, and this function will return the `RateLimit` error
+/// from the call that ran out. The rate-limit information in the returned `Response` is from
+/// whichever page had the fewest calls remaining, so you can tell how close the whole operation
+/// came to the limit.
+pub async fn classify_followers<T: Into<UserID>>(
+    acct: T,
+    token: &auth::Token,
+) -> Result<Response<FollowerClassification>> {
+    let acct = acct.into();
+    let followers = followers_ids(acct.clone(), token).with_page_size(5000);
+    let friends = friends_ids(acct, token).with_page_size(5000);
+
+    let (followers, follower_limit) = collect_ids(followers).await?;
+    let (friends, friend_limit) = collect_ids(friends).await?;
+
+    let rate_limit_status = lowest_remaining(follower_limit, friend_limit);
+    Ok(Response::new(
+        rate_limit_status,
+        FollowerClassification::new(&followers, &friends),
+    ))
+}
+
+/// Loads every ID from the given cursor, along with the rate-limit information from the page that
+/// had the fewest calls remaining.
+async fn collect_ids(
+    mut ids: cursor::CursorIter<cursor::IDCursor>,
+) -> Result<(Vec<u64>, Option<RateLimit>)> {
+    let mut all = Vec::new();
+    let mut rate_limit_status = None;
+
+    while let Some(id) = ids.try_next().await? {
+        rate_limit_status = Some(lowest_remaining(
+            rate_limit_status,
+            Some(id.rate_limit_status),
+        ));
+        all.push(id.response);
+    }
+
+    Ok((all, rate_limit_status))
+}
+
+fn lowest_remaining(left: Option<RateLimit>, right: Option<RateLimit>) -> RateLimit {
+    match (left, right) {
+        (Some(left), Some(right)) if right.remaining < left.remaining => right,
+        (Some(left), _) => left,
+        (None, Some(right)) => right,
+        (None, None) => RateLimit {
+            limit: -1,
+            remaining: -1,
+            reset: -1,
+        },
+    }
+}
+
 //---Cursored collections---
 
 /// Lookup users based on the given search term.
@@ -182,7 +425,13 @@ pub fn friends_of<T: Into<UserID>>(
     token: &auth::Token,
 ) -> cursor::CursorIter<cursor::UserCursor> {
     let params = ParamList::new().add_user_param(acct.into());
-    cursor::CursorIter::new(links::users::FRIENDS_LIST, token, Some(params), Some(20))
+    cursor::CursorIter::new(
+        links::users::FRIENDS_LIST,
+        token,
+        Some(params),
+        Some(20),
+        Some(200),
+    )
 }
 
 /// Lookup the users a given account follows, also called their "friends" within the API, but only
@@ -199,7 +448,13 @@ pub fn friends_ids<T: Into<UserID>>(
     token: &auth::Token,
 ) -> cursor::CursorIter<cursor::IDCursor> {
     let params = ParamList::new().add_user_param(acct.into());
-    cursor::CursorIter::new(links::users::FRIENDS_IDS, token, Some(params), Some(500))
+    cursor::CursorIter::new(
+        links::users::FRIENDS_IDS,
+        token,
+        Some(params),
+        Some(500),
+        Some(5000),
+    )
 }
 
 /// Lookup the users that follow a given account.
@@ -213,7 +468,13 @@ pub fn followers_of<T: Into<UserID>>(
     let params = ParamList::new()
         .extended_tweets()
         .add_user_param(acct.into());
-    cursor::CursorIter::new(links::users::FOLLOWERS_LIST, token, Some(params), Some(20))
+    cursor::CursorIter::new(
+        links::users::FOLLOWERS_LIST,
+        token,
+        Some(params),
+        Some(20),
+        Some(200),
+    )
 }
 
 /// Lookup the users that follow a given account, but only return their user IDs.
@@ -229,7 +490,13 @@ pub fn followers_ids<T: Into<UserID>>(
     token: &auth::Token,
 ) -> cursor::CursorIter<cursor::IDCursor> {
     let params = ParamList::new().add_user_param(acct.into());
-    cursor::CursorIter::new(links::users::FOLLOWERS_IDS, token, Some(params), Some(500))
+    cursor::CursorIter::new(
+        links::users::FOLLOWERS_IDS,
+        token,
+        Some(params),
+        Some(500),
+        Some(5000),
+    )
 }
 
 /// Lookup the users that have been blocked by the authenticated user.
@@ -239,7 +506,7 @@ pub fn followers_ids<T: Into<UserID>>(
 /// change the page size used by the network call. Setting `page_size` manually may result in an
 /// error from Twitter.
 pub fn blocks(token: &auth::Token) -> cursor::CursorIter<cursor::UserCursor> {
-    cursor::CursorIter::new(links::users::BLOCKS_LIST, token, None, None)
+    cursor::CursorIter::new(links::users::BLOCKS_LIST, token, None, None, None)
 }
 
 /// Lookup the users that have been blocked by the authenticated user, but only return their user
@@ -254,7 +521,7 @@ pub fn blocks(token: &auth::Token) -> cursor::CursorIter<cursor::UserCursor> {
 /// change the page size used by the network call. Setting `page_size` manually may result in an
 /// error from Twitter.
 pub fn blocks_ids(token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
-    cursor::CursorIter::new(links::users::BLOCKS_IDS, token, None, None)
+    cursor::CursorIter::new(links::users::BLOCKS_IDS, token, None, None, None)
 }
 
 /// Lookup the users that have been muted by the authenticated user.
@@ -264,7 +531,7 @@ pub fn blocks_ids(token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
 /// change the page size used by the network call. Setting `page_size` manually may result in an
 /// error from Twitter.
 pub fn mutes(token: &auth::Token) -> cursor::CursorIter<cursor::UserCursor> {
-    cursor::CursorIter::new(links::users::MUTES_LIST, token, None, None)
+    cursor::CursorIter::new(links::users::MUTES_LIST, token, None, None, None)
 }
 
 /// Lookup the users that have been muted by the authenticated user, but only return their user IDs.
@@ -278,19 +545,19 @@ pub fn mutes(token: &auth::Token) -> cursor::CursorIter<cursor::UserCursor> {
 /// change the page size used by the network call. Setting `page_size` manually may result in an
 /// error from Twitter.
 pub fn mutes_ids(token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
-    cursor::CursorIter::new(links::users::MUTES_IDS, token, None, None)
+    cursor::CursorIter::new(links::users::MUTES_IDS, token, None, None, None)
 }
 
 /// Lookup the user IDs who have pending requests to follow the authenticated protected user.
 ///
 /// If the authenticated user is not a protected account, this will return an empty collection.
 pub fn incoming_requests(token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
-    cursor::CursorIter::new(links::users::FRIENDSHIPS_INCOMING, token, None, None)
+    cursor::CursorIter::new(links::users::FRIENDSHIPS_INCOMING, token, None, None, None)
 }
 
 /// Lookup the user IDs with which the authenticating user has a pending follow request.
 pub fn outgoing_requests(token: &auth::Token) -> cursor::CursorIter<cursor::IDCursor> {
-    cursor::CursorIter::new(links::users::FRIENDSHIPS_OUTGOING, token, None, None)
+    cursor::CursorIter::new(links::users::FRIENDSHIPS_OUTGOING, token, None, None, None)
 }
 
 //---User actions---
@@ -298,9 +565,10 @@ pub fn outgoing_requests(token: &auth::Token) -> cursor::CursorIter<cursor::IDCu
 /// Follow the given account with the authenticated user, and set whether device notifications
 /// should be enabled.
 ///
-/// Upon success, the future returned by this function yields the user that was just followed, even
-/// when following a protected account. In the latter case, this indicates that the follow request
-/// was successfully sent.
+/// Upon success, the future returned by this function yields a `FollowOutcome` holding the user
+/// that was just followed. When following a protected account, this only means that the follow
+/// request was successfully sent; in that case, the outcome's `request_pending` field is `true`
+/// until the account owner approves the request.
 ///
 /// Calling this with an account the user already follows may return an error, or ("for performance
 /// reasons") may return success without changing any account settings.
@@ -308,13 +576,118 @@ pub async fn follow<T: Into<UserID>>(
     acct: T,
     notifications: bool,
     token: &auth::Token,
-) -> Result<Response<TwitterUser>> {
+) -> Result<Response<FollowOutcome>> {
+    follow_at(links::users::FOLLOW, acct.into(), notifications, token).await
+}
+
+/// Performs `follow` against the given URL.
+async fn follow_at(
+    link: &'static str,
+    acct: UserID,
+    notifications: bool,
+    token: &auth::Token,
+) -> Result<Response<FollowOutcome>> {
     let params = ParamList::new()
         .extended_tweets()
-        .add_user_param(acct.into())
+        .add_user_param(acct)
         .add_param("follow", notifications.to_string());
-    let req = post(links::users::FOLLOW, token, Some(&params));
-    request_with_json_response(req).await
+    let req = post(link, token, Some(&params));
+    let resp: Response<TwitterUser> = request_with_json_response(req).await?;
+    Ok(Response::into(resp))
+}
+
+/// Follow each of the given accounts in turn, pausing between them and whenever the rate limit
+/// runs out.
+///
+/// This calls `follow` for one account at a time, in the order they were given, and yields the
+/// outcome of each call from the returned stream. Each outcome carries the rate-limit information
+/// from its call.
+///
+/// Between follows, the stream waits for the next delay from `pacing`. The backoff is reset after
+/// every successful follow, so while calls succeed the pause is always its first delay, and a
+/// steady pace can be set with `Backoff::linear(pause, pause)`. After an error, the pauses grow
+/// according to the backoff until a follow succeeds again.
+///
+/// When a response says there are no calls left in the current rate window, the stream waits
+/// until the window resets before following the next account, instead of running into Twitter's
+/// rate-limit error. If a call is rejected with `Error::RateLimit` anyway, the stream waits for the
+/// reset and tries that account once more, as with `auto_retry`. Either way, if the next delay
+/// from `pacing` is longer than the wait for the reset, the stream waits for that instead.
+///
+/// Any other error is yielded in place of that account's outcome, and the stream moves on to the
+/// next account. Since a rate window can be up to 15 minutes long, working through a long list can
+/// take a while.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// use std::time::Duration;
+/// use egg_mode::Backoff;
+/// use futures::TryStreamExt;
+///
+/// let accts = vec!["rustlang", "ThisWeekInRust"];
+/// let pacing = Backoff::linear(Duration::from_secs(1), Duration::from_secs(30));
+/// egg_mode::user::follow_all(accts, false, pacing, &token)
+///     .try_for_each(|outcome| {
+///         println!("followed @{}", outcome.user.screen_name);
+///         futures::future::ok(())
+///     })
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn follow_all<'a, T, I>(
+    accts: I,
+    notifications: bool,
+    pacing: Backoff,
+    token: &'a auth::Token,
+) -> impl Stream<Item = Result<Response<FollowOutcome>>> + 'a
+where
+    T: Into<UserID>,
+    I: IntoIterator<Item = T>,
+{
+    let accts = accts.into_iter().map(Into::into).collect::<Vec<UserID>>();
+    follow_all_at(links::users::FOLLOW, accts, notifications, pacing, token)
+}
+
+/// Performs `follow_all` against the given URL.
+pub(crate) fn follow_all_at<'a>(
+    link: &'static str,
+    accts: Vec<UserID>,
+    notifications: bool,
+    pacing: Backoff,
+    token: &'a auth::Token,
+) -> impl Stream<Item = Result<Response<FollowOutcome>>> + 'a {
+    stream::unfold(
+        (accts.into_iter(), pacing, None),
+        move |(mut accts, mut pacing, wait)| async move {
+            let acct = accts.next()?;
+            if let Some(wait) = wait {
+                tokio::time::sleep(wait).await;
+            }
+
+            let res = auto_retry(1, pacing.clone(), || {
+                follow_at(link, acct.clone(), notifications, token)
+            })
+            .await;
+            if res.is_ok() {
+                pacing.reset();
+            }
+
+            let delay = pacing.next_delay();
+            let wait = match res {
+                Ok(ref resp) if resp.rate_limit_status.remaining == 0 => {
+                    until_reset(resp.rate_limit_status.reset, chrono::Utc::now().timestamp())
+                        .map_or(delay, |wait| wait.max(delay))
+                }
+                _ => delay,
+            };
+
+            Some((res, (accts, pacing, Some(wait))))
+        },
+    )
 }
 
 /// Unfollow the given account with the authenticated user.
@@ -334,6 +707,26 @@ pub async fn unfollow<T: Into<UserID>>(
     request_with_json_response(req).await
 }
 
+/// Cancel a pending request to follow the given protected account.
+///
+/// Twitter uses the same endpoint for cancelling follow requests as it does for unfollowing an
+/// account, so this calls the same endpoint as `unfollow`. The difference is that this checks that
+/// the returned user's `follow_request_sent` field is no longer set, and returns an error of
+/// `FollowRequestNotCancelled` if the request is still pending.
+///
+/// Upon success, the future returned by this function yields the user whose follow request was
+/// cancelled.
+pub async fn cancel_follow_request<T: Into<UserID>>(
+    acct: T,
+    token: &auth::Token,
+) -> Result<Response<TwitterUser>> {
+    let resp = unfollow(acct, token).await?;
+    if resp.follow_request_sent == Some(true) {
+        return Err(error::Error::FollowRequestNotCancelled(resp.id));
+    }
+    Ok(resp)
+}
+
 /// Update notification settings and reweet visibility for the given user.
 ///
 /// Calling this for an account the authenticated user does not already follow will not cause them
@@ -370,14 +763,33 @@ pub async fn block<T: Into<UserID>>(acct: T, token: &auth::Token) -> Result<Resp
 
 /// Block the given account and report it for spam, with the authenticated user.
 ///
+/// To report an account without blocking it, use `report_spam_with`.
+///
 /// Upon success, the future returned by this function yields the given user.
 pub async fn report_spam<T: Into<UserID>>(
     acct: T,
     token: &auth::Token,
 ) -> Result<Response<TwitterUser>> {
-    let params = ParamList::new()
+    report_spam_with(acct, true, token).await
+}
+
+/// Report the given account for spam, with the authenticated user, optionally blocking it as well.
+///
+/// If `perform_block` is `true`, this is the same as `report_spam`. If it's `false`, the account
+/// is reported without being blocked.
+///
+/// Upon success, the future returned by this function yields the given user.
+pub async fn report_spam_with<T: Into<UserID>>(
+    acct: T,
+    perform_block: bool,
+    token: &auth::Token,
+) -> Result<Response<TwitterUser>> {
+    let mut params = ParamList::new()
         .extended_tweets()
         .add_user_param(acct.into());
+    if !perform_block {
+        params.add_param_ref("perform_block", "false");
+    }
     let req = post(links::users::REPORT_SPAM, token, Some(&params));
     request_with_json_response(req).await
 }
@@ -420,3 +832,12 @@ pub async fn unmute<T: Into<UserID>>(
     let req = post(links::users::UNMUTE, token, Some(&params));
     request_with_json_response(req).await
 }
+
+/// Parse a single user from the given JSON text, as Twitter sends it.
+///
+/// This is meant for users that arrive without going through egg-mode, like the payloads the
+/// Account Activity API posts to a webhook, or JSON saved from an earlier call. Users serialized
+/// by egg-mode can be read back with this as well.
+pub fn from_json_str(json: &str) -> Result<TwitterUser> {
+    Ok(serde_json::from_str(json)?)
+}
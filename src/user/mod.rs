@@ -20,6 +20,7 @@
 //!   these types (`TwitterUser` contains the other two) describe the content of a user's profile,
 //!   and a handful of settings relating to how their profile is displayed.
 //! - `UserSearch`: returned by `search`, this is a stream of search results.
+//! - `UserCache`: an in-memory cache of users, to avoid loading the same users over and over.
 //!
 //! ## Functions
 //!
@@ -38,9 +39,12 @@
 //! results.
 //!
 //! - `show`
+//! - `pinned_tweet`
+//! - `profile_with_tweets`
 //! - `lookup`/`lookup_ids`/`lookup_names`
 //! - `friends_no_retweets`
 //! - `relation`/`relation_lookup`
+//! - `classify_followers`
 //!
 //! ### Cursored lookup
 //!
@@ -54,7 +58,12 @@
 //! - `blocks`/`blocks_ids`
 //! - `mutes`/`mutes_ids`
 //! - `incoming_requests`/`outgoing_requests`
+//!
+//! ### Parsing
+//!
+//! - `from_json_str`: For users that were received outside of egg-mode, like through a webhook.
 
+use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -67,9 +76,11 @@ use serde::{Deserialize, Serialize};
 use crate::common::*;
 use crate::{auth, entities, error, links, tweet};
 
+mod cache;
 mod fun;
 mod raw;
 
+pub use self::cache::*;
 pub use self::fun::*;
 
 /// Convenience enum to generalize between referring to an account by numeric ID or by screen name.
@@ -81,14 +92,31 @@ pub use self::fun::*;
 ///
 /// * `u64`
 /// * `&u64` (convenient when used with iterators)
-/// * `&str`
-/// * `&&str` (convenient when used with iterators)
+/// * `&'static str`
+/// * `String`
 /// * `&String` (to counteract the fact that deref coercion doesn't work with generics)
 /// * `&UserID` (convenient when used with iterators)
+/// * `&TwitterUser` (using the user's numeric ID)
 ///
 /// This way, when a function in egg-mode has a paremeter of type `T: Into<UserID>`, you can
 /// call it with any of these types, and it will be converted automatically. egg-mode will then use
 /// the proper parameter when performing the call to Twitter.
+///
+/// Since a `UserID` doesn't borrow from what it was made from, converting from a `&String` or a
+/// `&UserID` clones the screen name.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// let rustlang = egg_mode::user::show("rustlang", &token).await.unwrap().response;
+/// egg_mode::user::follow(&rustlang, false, &token).await.unwrap();
+///
+/// let name = String::from("ThisWeekInRust");
+/// egg_mode::user::follow(&name, false, &token).await.unwrap();
+/// # }
+/// ```
 #[derive(Debug, Clone, derive_more::From)]
 pub enum UserID {
     /// Referring via the account's numeric ID.
@@ -109,6 +137,30 @@ impl From<String> for UserID {
     }
 }
 
+impl<'a> From<&'a String> for UserID {
+    fn from(name: &'a String) -> UserID {
+        UserID::ScreenName(name.clone().into())
+    }
+}
+
+impl<'a> From<&'a u64> for UserID {
+    fn from(id: &'a u64) -> UserID {
+        UserID::ID(*id)
+    }
+}
+
+impl<'a> From<&'a UserID> for UserID {
+    fn from(id: &'a UserID) -> UserID {
+        id.clone()
+    }
+}
+
+impl<'a> From<&'a TwitterUser> for UserID {
+    fn from(user: &'a TwitterUser) -> UserID {
+        UserID::ID(user.id)
+    }
+}
+
 round_trip! { raw::RawTwitterUser,
     /// Represents a Twitter user.
     ///
@@ -179,6 +231,12 @@ round_trip! { raw::RawTwitterUser,
         /// Link information that has been parsed out of the `url` or `description` fields given by the
         /// user.
         pub entities: UserEntities,
+        /// The email address associated with this account.
+        ///
+        /// This is only filled in for the authenticated user, when loaded with
+        /// `account::verify_credentials` with `include_email` set, and only if the app has been
+        /// granted permission by Twitter to request users' email addresses.
+        pub email: Option<String>,
         /// The number of tweets this user has favorited or liked in the account's lifetime.
         /// The term "favourites" and its British spelling are used for historical reasons.
         pub favourites_count: i32,
@@ -216,6 +274,15 @@ round_trip! { raw::RawTwitterUser,
         pub location: Option<String>,
         /// The user-entered display name.
         pub name: String,
+        /// The IDs of the tweets this user has pinned to their profile, if any.
+        ///
+        /// Twitter only lets a user pin one tweet at a time, so this will have at most one entry in
+        /// practice. This is not part of the documented user object, so it may be empty even for users
+        /// that have pinned a tweet. To load the tweet itself, use [`pinned_tweet`].
+        ///
+        /// [`pinned_tweet`]: fn.pinned_tweet.html
+        #[serde(default)]
+        pub pinned_tweet_ids: Vec<u64>,
         /// The hex color chosen by the user for their profile background.
         pub profile_background_color: String,
         /// A URL pointing to the background image chosen by the user for their profile. Uses
@@ -290,9 +357,13 @@ round_trip! { raw::RawTwitterUser,
         /// Indicates whether this user is a verified account.
         pub verified: bool,
         /// When present, lists the countries this user has been withheld from.
+        ///
+        /// The same special codes as on `Tweet::withheld_in_countries` apply here. Use
+        /// `is_withheld_in` to check a specific country.
         pub withheld_in_countries: Option<Vec<String>>,
-        /// When present, indicates whether the content being withheld is a "status" or "user".
-        pub withheld_scope: Option<String>,
+        /// When present, indicates whether the content being withheld is this user's tweets, or the
+        /// user themselves.
+        pub withheld_scope: Option<tweet::WithheldScope>,
     }
 }
 
@@ -319,6 +390,7 @@ impl From<raw::RawTwitterUser> for TwitterUser {
             default_profile_image: raw.default_profile_image,
             description: raw.description,
             entities: raw.entities,
+            email: raw.email,
             favourites_count: raw.favourites_count,
             follow_request_sent: raw.follow_request_sent,
             followers_count: raw.followers_count,
@@ -330,6 +402,7 @@ impl From<raw::RawTwitterUser> for TwitterUser {
             listed_count: raw.listed_count,
             location: raw.location,
             name: raw.name,
+            pinned_tweet_ids: raw.pinned_tweet_ids,
             profile_background_color: raw.profile_background_color,
             profile_background_image_url: raw.profile_background_image_url,
             profile_background_image_url_https: raw.profile_background_image_url_https,
@@ -357,6 +430,88 @@ impl From<raw::RawTwitterUser> for TwitterUser {
     }
 }
 
+impl TwitterUser {
+    /// Returns the website link from this user's profile, with Twitter's t.co shortening undone.
+    ///
+    /// This is the `expanded_url` of the first URL entity for the `url` field, if Twitter gave
+    /// one, or the `url` field itself otherwise. If the user hasn't set a website, this returns
+    /// `None`.
+    pub fn website(&self) -> Option<&str> {
+        let expanded = self
+            .entities
+            .url
+            .as_ref()
+            .and_then(|detail| detail.urls.first())
+            .and_then(|entity| entity.expanded_url.as_deref());
+
+        expanded
+            .or(self.url.as_deref())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Returns whether this user's content is withheld in the country with the given two-letter
+    /// country code.
+    ///
+    /// This also returns `true` if the user is withheld in all countries.
+    pub fn is_withheld_in(&self, country: &str) -> bool {
+        tweet::withheld_in(self.withheld_in_countries.as_deref(), country)
+    }
+}
+
+/// Options for what to load along with user profiles in `show_with` and `lookup_with`.
+///
+/// By default, Twitter returns each profile with its entities and the user's most recent tweet.
+/// Options that aren't set aren't sent to Twitter at all, so the default options make the same
+/// request as `show` and `lookup`.
+///
+/// ```rust,no_run
+/// # use egg_mode::Token;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token: Token = unimplemented!();
+/// use egg_mode::user::{self, UserOptions};
+///
+/// let options = UserOptions::new().skip_status(true);
+/// let users = user::lookup_with(vec!["rustlang", "ThisWeekInRust"], &options, &token)
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UserOptions {
+    include_entities: Option<bool>,
+    skip_status: Option<bool>,
+}
+
+impl UserOptions {
+    /// Creates a new set of options with the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to load the `entities` of each user's profile, and of their most recent tweet.
+    pub fn include_entities(self, include_entities: bool) -> Self {
+        UserOptions {
+            include_entities: Some(include_entities),
+            ..self
+        }
+    }
+
+    /// Sets whether to leave each user's most recent tweet out of their profile.
+    pub fn skip_status(self, skip_status: bool) -> Self {
+        UserOptions {
+            skip_status: Some(skip_status),
+            ..self
+        }
+    }
+
+    pub(crate) fn add_params(&self, params: ParamList) -> ParamList {
+        params
+            .add_opt_param("include_entities", self.include_entities.map_string())
+            .add_opt_param("skip_status", self.skip_status.map_string())
+    }
+}
+
 /// Container for URL entity information that may be paired with a user's profile.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct UserEntities {
@@ -381,6 +536,12 @@ pub struct UserEntityDetail {
     pub urls: Vec<entities::UrlEntity>,
 }
 
+/// The most users Twitter returns in one page of a user search.
+const MAX_SEARCH_PAGE_SIZE: i32 = 20;
+
+/// The most results Twitter gives access to in a user search.
+const MAX_SEARCH_RESULTS: i64 = 1000;
+
 /// Represents an active user search.
 ///
 /// This struct is returned by [`search`][] and is meant to be used as a `Stream`. That means all
@@ -431,6 +592,10 @@ pub struct UserEntityDetail {
 /// `start_at_page` lets you start your search at a specific page. Calling either of these after
 /// starting iteration will clear any current results.
 ///
+/// Twitter only gives access to the first 1,000 results of a user search, and returns an error
+/// for pages past that point. The `Stream` implementation ends once it reaches this limit, so the
+/// page size and starting page only change how those 1,000 results are split into network calls.
+///
 /// The `Stream` implementation yields `Response<TwitterUser>` on a successful iteration, and
 /// `Error` for errors, so network errors, rate-limit errors and other issues are passed directly
 /// through in `poll()`. The `Stream` implementation will allow you to poll again after an error to
@@ -475,6 +640,8 @@ pub struct UserSearch {
     /// The current page of results being returned, starting at 1.
     pub page_num: i32,
     /// The number of user records per page of results. Defaults to 10, maximum of 20.
+    ///
+    /// `with_page_size` keeps this between 1 and 20, but setting it directly doesn't.
     pub page_size: i32,
     current_loader: Option<FutureResponse<Vec<TwitterUser>>>,
     current_results: Option<VecIter<TwitterUser>>,
@@ -483,11 +650,12 @@ pub struct UserSearch {
 impl UserSearch {
     /// Sets the page size used for the search query.
     ///
-    /// Calling this will invalidate any current search results, making the next call to `next()`
-    /// perform a network call.
+    /// Twitter allows at most 20 users per page, so values larger than that are lowered to 20, and
+    /// values smaller than 1 are raised to 1. Calling this will invalidate any current search
+    /// results, making the next call to `next()` perform a network call.
     pub fn with_page_size(self, page_size: i32) -> Self {
         UserSearch {
-            page_size,
+            page_size: page_size.clamp(1, MAX_SEARCH_PAGE_SIZE),
             current_loader: None,
             current_results: None,
             ..self
@@ -497,7 +665,9 @@ impl UserSearch {
     /// Sets the starting page number for the search query.
     ///
     /// The search method begins numbering pages at 1. Calling this will invalidate any current
-    /// search results, making the next call to `next()` perform a network call.
+    /// search results, making the next call to `next()` perform a network call. If the given page
+    /// starts past the first 1,000 results, the `Stream` implementation will end without loading
+    /// anything.
     pub fn start_at_page(self, page_num: i32) -> Self {
         UserSearch {
             page_num,
@@ -511,7 +681,8 @@ impl UserSearch {
     ///
     /// This will automatically be called if you use the `UserSearch` as an iterator. This method is
     /// made public for convenience if you want to manage the pagination yourself. Remember to
-    /// change `page_num` between calls.
+    /// change `page_num` between calls, and that Twitter will return an error for pages past the
+    /// first 1,000 results.
     pub fn call(&self) -> impl Future<Output = error::Result<Response<Vec<TwitterUser>>>> {
         let params = ParamList::new()
             .add_param("q", self.query.clone())
@@ -533,6 +704,12 @@ impl UserSearch {
             current_results: None,
         }
     }
+
+    /// Returns whether the current page starts past the results Twitter allows access to.
+    fn past_result_limit(&self) -> bool {
+        let skipped = i64::from(self.page_num - 1) * i64::from(self.page_size);
+        skipped >= MAX_SEARCH_RESULTS
+    }
 }
 
 impl Stream for UserSearch {
@@ -564,6 +741,10 @@ impl Stream for UserSearch {
             }
         }
 
+        if self.past_result_limit() {
+            return Poll::Ready(None);
+        }
+
         self.current_loader = Some(Box::pin(self.call()));
         self.poll_next(cx)
     }
@@ -583,6 +764,34 @@ pub struct Relationship {
     pub source: RelationSource,
 }
 
+impl Relationship {
+    /// Returns whether the source account follows the target account.
+    pub fn is_following(&self) -> bool {
+        self.source.following
+    }
+
+    /// Returns whether the target account follows the source account.
+    pub fn is_followed_by(&self) -> bool {
+        self.source.followed_by
+    }
+
+    /// Returns whether the source account has blocked the target account.
+    ///
+    /// This is only known when the source account is the authenticated user; otherwise, this
+    /// returns `false`.
+    pub fn is_blocked(&self) -> bool {
+        self.source.blocking.unwrap_or(false)
+    }
+
+    /// Returns whether the source account has muted the target account.
+    ///
+    /// This is only known when the source account is the authenticated user; otherwise, this
+    /// returns `false`.
+    pub fn is_muted(&self) -> bool {
+        self.source.muting.unwrap_or(false)
+    }
+}
+
 /// Represents relationship settings between two Twitter accounts, from the perspective of the
 /// target user.
 #[derive(Debug, Deserialize)]
@@ -622,6 +831,9 @@ pub struct RelationSource {
     /// Indicates whether this source account is blocking the target account. If the source account
     /// is not the authenticated user, holds `None` instead.
     pub blocking: Option<bool>,
+    /// Indicates whether this source account has muted the target account. If the source account
+    /// is not the authenticated user, holds `None` instead.
+    pub muting: Option<bool>,
     /// Indicates whether this source account has reported the target account for spam. If the source
     /// account is not the authenticated user, holds `None` instead.
     pub marked_spam: Option<bool>,
@@ -658,8 +870,89 @@ pub struct RelationLookup {
     pub connections: Vec<Connection>,
 }
 
+impl RelationLookup {
+    /// Returns whether the authenticated user follows the target account.
+    pub fn is_following(&self) -> bool {
+        self.connections.contains(&Connection::Following)
+    }
+
+    /// Returns whether the target account follows the authenticated user.
+    pub fn is_followed_by(&self) -> bool {
+        self.connections.contains(&Connection::FollowedBy)
+    }
+
+    /// Returns whether the authenticated user has blocked the target account.
+    pub fn is_blocked(&self) -> bool {
+        self.connections.contains(&Connection::Blocking)
+    }
+
+    /// Returns whether the authenticated user has muted the target account.
+    pub fn is_muted(&self) -> bool {
+        self.connections.contains(&Connection::Muting)
+    }
+}
+
+/// The followers and friends of an account, sorted by whether the follow goes both ways.
+///
+/// This is returned by `classify_followers`. Each list keeps the order Twitter returned the IDs
+/// in, which is currently most-recent first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FollowerClassification {
+    /// Accounts that follow the account and are followed back.
+    pub mutuals: Vec<u64>,
+    /// Accounts that follow the account, but aren't followed back.
+    pub fans: Vec<u64>,
+    /// Accounts that the account follows, but which don't follow it back.
+    pub following_only: Vec<u64>,
+}
+
+impl FollowerClassification {
+    /// Sorts the given lists of follower and friend IDs.
+    pub(crate) fn new(followers: &[u64], friends: &[u64]) -> FollowerClassification {
+        let follower_set = followers.iter().collect::<HashSet<_>>();
+        let friend_set = friends.iter().collect::<HashSet<_>>();
+
+        let (mutuals, fans) = followers.iter().partition(|id| friend_set.contains(id));
+        let following_only = friends
+            .iter()
+            .filter(|id| !follower_set.contains(id))
+            .copied()
+            .collect();
+
+        FollowerClassification {
+            mutuals,
+            fans,
+            following_only,
+        }
+    }
+}
+
+/// The result of following an account with `follow`.
+///
+/// Following a protected account doesn't take effect right away: it sends a follow request, which
+/// the account owner has to approve. `request_pending` tells these cases apart, so an app can show
+/// whether the user is now following the account or waiting on approval.
+#[derive(Debug, Clone)]
+pub struct FollowOutcome {
+    /// The account that was followed.
+    pub user: TwitterUser,
+    /// Whether the account is protected and the follow is waiting for its owner's approval.
+    ///
+    /// This is taken from the `follow_request_sent` field of `user`.
+    pub request_pending: bool,
+}
+
+impl From<TwitterUser> for FollowOutcome {
+    fn from(user: TwitterUser) -> FollowOutcome {
+        FollowOutcome {
+            request_pending: user.follow_request_sent.unwrap_or(false),
+            user,
+        }
+    }
+}
+
 /// Represents the ways a target account can be connected to another account.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum Connection {
     /// The target account has no relation.
     #[serde(rename = "none")]
@@ -688,6 +981,7 @@ pub enum Connection {
 mod tests {
     use super::TwitterUser;
     use crate::common::tests::load_file;
+    use crate::common::ParamList;
 
     #[test]
     fn roundtrip_deser() {
@@ -699,4 +993,393 @@ mod tests {
 
         assert_eq!(json1, json2);
     }
+
+    #[test]
+    fn classify_followers() {
+        use super::FollowerClassification;
+
+        let followers = [5, 4, 3, 2];
+        let friends = [6, 4, 2, 1];
+        let sorted = FollowerClassification::new(&followers, &friends);
+
+        assert_eq!(sorted.mutuals, vec![4, 2]);
+        assert_eq!(sorted.fans, vec![5, 3]);
+        assert_eq!(sorted.following_only, vec![6, 1]);
+    }
+
+    #[test]
+    fn follow_outcome() {
+        use super::FollowOutcome;
+
+        let sample = load_file("sample_payloads/user_array.json");
+        let mut users: Vec<TwitterUser> = serde_json::from_str(&sample).unwrap();
+        let mut user = users.remove(0);
+
+        user.follow_request_sent = None;
+        assert!(!FollowOutcome::from(user.clone()).request_pending);
+
+        user.follow_request_sent = Some(true);
+        let outcome = FollowOutcome::from(user);
+        assert!(outcome.request_pending);
+        assert_eq!(outcome.user.follow_request_sent, Some(true));
+    }
+
+    #[test]
+    fn search_result_limit() {
+        let token = crate::Token::Bearer(String::new());
+
+        let search = super::search("rustlang", &token).with_page_size(50);
+        assert_eq!(search.page_size, 20);
+        assert!(!search.past_result_limit());
+
+        let search = search.start_at_page(50);
+        assert!(!search.past_result_limit());
+        let search = search.start_at_page(51);
+        assert!(search.past_result_limit());
+
+        let search = search.with_page_size(0);
+        assert_eq!(search.page_size, 1);
+        assert!(!search.past_result_limit());
+    }
+
+    #[test]
+    fn cache_eviction() {
+        use super::{UserCache, UserID};
+        use std::time::Duration;
+
+        let sample = load_file("sample_payloads/user_array.json");
+        let users: Vec<TwitterUser> = serde_json::from_str(&sample).unwrap();
+        let token = crate::Token::Bearer(String::new());
+        let mut cache = UserCache::new(&token, 2, Duration::from_secs(60));
+
+        let user = |idx: usize, id: u64, name: &str| {
+            let mut user = users[idx % users.len()].clone();
+            user.id = id;
+            user.screen_name = name.to_string();
+            user
+        };
+
+        cache.insert(user(0, 1, "One"));
+        cache.insert(user(1, 2, "Two"));
+        assert_eq!(cache.get_cached(&UserID::from("one")).unwrap().id, 1);
+
+        cache.insert(user(2, 3, "Three"));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_cached(&UserID::ID(2)).is_none());
+        assert!(cache.get_cached(&UserID::from("two")).is_none());
+        assert!(cache.get_cached(&UserID::ID(1)).is_some());
+
+        cache.remove(&UserID::from("THREE"));
+        assert_eq!(cache.len(), 1);
+
+        let mut stale = UserCache::new(&token, 2, Duration::from_secs(0));
+        stale.insert(user(0, 1, "One"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(stale.get_cached(&UserID::ID(1)).is_none());
+    }
+
+    #[test]
+    fn website() {
+        let sample = load_file("sample_payloads/user_array.json");
+        let mut users: Vec<TwitterUser> = serde_json::from_str(&sample).unwrap();
+        let mut user = users.remove(0);
+
+        user.url = Some("https://t.co/abc123".to_string());
+        user.entities.url = Some(super::UserEntityDetail {
+            urls: vec![crate::entities::UrlEntity {
+                display_url: "example.com".to_string(),
+                expanded_url: Some("https://example.com/".to_string()),
+                range: (0, 19),
+                url: "https://t.co/abc123".to_string(),
+            }],
+        });
+        assert_eq!(user.website(), Some("https://example.com/"));
+
+        user.entities.url = None;
+        assert_eq!(user.website(), Some("https://t.co/abc123"));
+
+        user.url = Some(String::new());
+        assert_eq!(user.website(), None);
+
+        user.url = None;
+        assert_eq!(user.website(), None);
+    }
+
+    #[test]
+    fn user_id_conversions() {
+        use super::UserID;
+
+        let sample = load_file("sample_payloads/user_array.json");
+        let users: Vec<TwitterUser> = serde_json::from_str(&sample).unwrap();
+        match UserID::from(&users[3]) {
+            UserID::ID(id) => assert_eq!(id, 165262228),
+            other => panic!("expected an ID, got {:?}", other),
+        }
+
+        let name = String::from("rustlang");
+        let ids = [&name].iter().map(|n| UserID::from(*n)).collect::<Vec<_>>();
+        match UserID::from(&ids[0]) {
+            UserID::ScreenName(n) => assert_eq!(n, "rustlang"),
+            other => panic!("expected a screen name, got {:?}", other),
+        }
+
+        let nums = [1u64, 2];
+        let (ids, names) = crate::common::multiple_names_param(nums.iter());
+        assert_eq!(ids, "1,2");
+        assert_eq!(names, "");
+    }
+
+    #[tokio::test]
+    async fn lookup_batches() {
+        use crate::common::tests::{rate_limit_headers, user_page};
+        use crate::raw::{with_transport, MockTransport};
+        use hyper::{Method, StatusCode};
+
+        // Twitter doesn't keep the order of the requested users, so serve each batch backwards
+        let first = (1..=100).rev().collect::<Vec<u64>>();
+        let second = (101..=150).rev().collect::<Vec<u64>>();
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::OK,
+                rate_limit_headers(900, 899, 1600000000),
+                user_page(&first),
+            )
+            .respond(
+                StatusCode::OK,
+                rate_limit_headers(900, 898, 1600000000),
+                user_page(&second),
+            );
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let users = with_transport(mock.clone(), super::lookup(1..=150, &token))
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        for request in &requests {
+            assert_eq!(request.method, Method::POST);
+            assert_eq!(request.uri.path(), "/1.1/users/lookup.json");
+        }
+        let body = String::from_utf8(requests[1].body.clone()).unwrap();
+        assert!(body.contains("user_id=101%2C102%2C"));
+
+        assert_eq!(users.len(), 150);
+        let ids = users.iter().map(|u| u.id).collect::<Vec<_>>();
+        assert_eq!(ids, (1..=150).collect::<Vec<_>>());
+        assert_eq!(users.rate_limit_status.remaining, 898);
+
+        let none = with_transport(mock.clone(), super::lookup(Vec::<u64>::new(), &token))
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn follow_all_pauses() {
+        use crate::common::tests::rate_limit_headers;
+        use crate::common::until_reset;
+        use crate::raw::{with_transport, Headers, MockTransport};
+        use futures::StreamExt;
+        use hyper::StatusCode;
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(
+            until_reset(now as i32 + 5, now),
+            Some(Duration::from_secs(6))
+        );
+        assert_eq!(until_reset(now as i32 - 5, now), None);
+
+        let sample = load_file("sample_payloads/user_array.json");
+        let users: Vec<serde_json::Value> = serde_json::from_str(&sample).unwrap();
+        let already_requested =
+            r#"{"errors":[{"code":160,"message":"You've already requested to follow user."}]}"#;
+
+        // the third follow uses up the rate window, which resets ten minutes from `now`
+        let mock = MockTransport::new()
+            .respond(
+                StatusCode::OK,
+                rate_limit_headers(15, 14, now + 1),
+                users[2].to_string(),
+            )
+            .respond(StatusCode::FORBIDDEN, Headers::new(), already_requested)
+            .respond(
+                StatusCode::OK,
+                rate_limit_headers(15, 0, now + 600),
+                users[3].to_string(),
+            )
+            .respond(
+                StatusCode::OK,
+                rate_limit_headers(15, 14, now + 901),
+                users[2].to_string(),
+            );
+        let token = crate::auth::Token::Bearer("abc".to_string());
+
+        let step = Duration::from_millis(100);
+        let pacing = crate::Backoff::linear(step, Duration::from_secs(1));
+        let accts = vec!["TwitterDev", "protected", "rustlang", "TwitterDev"];
+        let outcomes = with_transport(
+            mock.clone(),
+            super::follow_all(accts, false, pacing, &token)
+                .map(|outcome| (Instant::now(), outcome))
+                .collect::<Vec<_>>(),
+        )
+        .await;
+        let gap = |idx: usize| outcomes[idx + 1].0 - outcomes[idx].0;
+
+        assert_eq!(mock.requests().len(), 4);
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(
+            outcomes[0].1.as_ref().unwrap().user.screen_name,
+            "TwitterDev"
+        );
+        assert!(outcomes[1].1.is_err());
+        assert_eq!(outcomes[2].1.as_ref().unwrap().user.screen_name, "rustlang");
+        assert_eq!(
+            outcomes[2].1.as_ref().unwrap().rate_limit_status.remaining,
+            0
+        );
+        assert_eq!(
+            outcomes[3].1.as_ref().unwrap().rate_limit_status.remaining,
+            14
+        );
+
+        // tokio's clock is paused, so the gaps are exactly the pauses: a success waits the first
+        // step, an error waits the next one, and a spent rate window waits for the reset instead
+        assert_eq!(gap(0), step);
+        assert_eq!(gap(1), step * 2);
+        assert!(gap(2) >= Duration::from_secs(600));
+        assert!(gap(2) <= Duration::from_secs(601));
+    }
+
+    #[test]
+    fn user_options_params() {
+        use super::UserOptions;
+
+        let base = || ParamList::new().add_param("screen_name", "rustlang");
+
+        let plain = UserOptions::new().add_params(base());
+        assert_eq!(plain.to_urlencoded(), base().to_urlencoded());
+
+        let entities = UserOptions::new()
+            .include_entities(false)
+            .add_params(base());
+        assert_eq!(entities.get("include_entities").unwrap(), "false");
+        assert!(entities.get("skip_status").is_none());
+
+        let status = UserOptions::new().skip_status(true).add_params(base());
+        assert_eq!(status.get("skip_status").unwrap(), "true");
+        assert!(status.get("include_entities").is_none());
+    }
+
+    #[tokio::test]
+    async fn report_spam_params() {
+        use crate::raw::{with_transport, MockTransport};
+        use hyper::StatusCode;
+
+        let user = serde_json::from_str::<Vec<serde_json::Value>>(&load_file(
+            "sample_payloads/user_array.json",
+        ))
+        .unwrap()
+        .remove(3);
+        let token = crate::auth::Token::Bearer("abc".to_string());
+        let body_of = |mock: &MockTransport| {
+            let requests = mock.requests();
+            assert_eq!(requests.len(), 1);
+            assert_eq!(requests[0].uri.path(), "/1.1/users/report_spam.json");
+            String::from_utf8(requests[0].body.clone()).unwrap()
+        };
+
+        let mock = MockTransport::new().respond(
+            StatusCode::OK,
+            crate::common::Headers::new(),
+            user.to_string(),
+        );
+        with_transport(mock.clone(), super::report_spam("spammer", &token))
+            .await
+            .unwrap();
+        let body = body_of(&mock);
+        assert!(body.split('&').any(|p| p == "screen_name=spammer"));
+        assert!(!body.contains("perform_block"));
+
+        let mock = MockTransport::new().respond(
+            StatusCode::OK,
+            crate::common::Headers::new(),
+            user.to_string(),
+        );
+        with_transport(
+            mock.clone(),
+            super::report_spam_with("spammer", false, &token),
+        )
+        .await
+        .unwrap();
+        let body = body_of(&mock);
+        assert!(body.split('&').any(|p| p == "screen_name=spammer"));
+        assert!(body.split('&').any(|p| p == "perform_block=false"));
+    }
+
+    #[test]
+    fn relation_helpers() {
+        use super::{RelationLookup, Relationship};
+
+        let lookup: Vec<RelationLookup> = serde_json::from_str(
+            r#"[
+                {"name": "Rust", "screen_name": "rustlang", "id": 165262228,
+                 "connections": ["following", "followed_by"]},
+                {"name": "Spam", "screen_name": "spam", "id": 1,
+                 "connections": ["blocking", "muting"]},
+                {"name": "Nobody", "screen_name": "nobody", "id": 2,
+                 "connections": ["none"]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(lookup[0].is_following());
+        assert!(lookup[0].is_followed_by());
+        assert!(!lookup[0].is_blocked());
+        assert!(!lookup[0].is_muted());
+
+        assert!(!lookup[1].is_following());
+        assert!(lookup[1].is_blocked());
+        assert!(lookup[1].is_muted());
+
+        assert!(!lookup[2].is_following());
+        assert!(!lookup[2].is_followed_by());
+        assert!(!lookup[2].is_blocked());
+        assert!(!lookup[2].is_muted());
+
+        let mine: Relationship = serde_json::from_str(
+            r#"{
+                "source": {"id": 1, "screen_name": "me", "following": false, "followed_by": true,
+                           "can_dm": true, "blocking": true, "muting": false,
+                           "marked_spam": false, "all_replies": false, "want_retweets": true,
+                           "notifications_enabled": false},
+                "target": {"id": 2, "screen_name": "them", "following": true,
+                           "followed_by": false}
+            }"#,
+        )
+        .unwrap();
+        assert!(!mine.is_following());
+        assert!(mine.is_followed_by());
+        assert!(mine.is_blocked());
+        assert!(!mine.is_muted());
+
+        let others: Relationship = serde_json::from_str(
+            r#"{
+                "source": {"id": 3, "screen_name": "a", "following": true, "followed_by": false,
+                           "can_dm": false, "blocking": null, "muting": null,
+                           "marked_spam": null, "all_replies": null, "want_retweets": null,
+                           "notifications_enabled": null},
+                "target": {"id": 4, "screen_name": "b", "following": false, "followed_by": true}
+            }"#,
+        )
+        .unwrap();
+        assert!(others.is_following());
+        assert!(!others.is_followed_by());
+        assert!(!others.is_blocked());
+        assert!(!others.is_muted());
+    }
 }
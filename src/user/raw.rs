@@ -30,6 +30,9 @@ pub struct RawTwitterUser {
     /// user.
     #[serde(default)]
     pub entities: UserEntities,
+    /// The email address associated with this account, if it was requested and the app has
+    /// permission to see it.
+    pub email: Option<String>,
     /// The number of tweets this user has favorited or liked in the account's lifetime.
     /// The term "favourites" and its British spelling are used for historical reasons.
     pub favourites_count: i32,
@@ -67,6 +70,15 @@ pub struct RawTwitterUser {
     pub location: Option<String>,
     /// The user-entered display name.
     pub name: String,
+    /// The IDs of the tweets this user has pinned to their profile, if any.
+    ///
+    /// Twitter only lets a user pin one tweet at a time, so this will have at most one entry in
+    /// practice. This is not part of the documented user object, so it may be empty even for users
+    /// that have pinned a tweet. To load the tweet itself, use [`pinned_tweet`].
+    ///
+    /// [`pinned_tweet`]: fn.pinned_tweet.html
+    #[serde(default)]
+    pub pinned_tweet_ids: Vec<u64>,
     /// The hex color chosen by the user for their profile background.
     pub profile_background_color: String,
     /// A URL pointing to the background image chosen by the user for their profile. Uses
@@ -143,5 +155,5 @@ pub struct RawTwitterUser {
     /// When present, lists the countries this user has been withheld from.
     pub withheld_in_countries: Option<Vec<String>>,
     /// When present, indicates whether the content being withheld is a "status" or "user".
-    pub withheld_scope: Option<String>,
+    pub withheld_scope: Option<tweet::WithheldScope>,
 }